@@ -1,21 +1,11 @@
-#![feature(iter_array_chunks)]
+use aoc_intervals::IntervalSet;
 
-use std::ops::RangeInclusive;
-
-fn contained(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
-    a_contains_b(a, b) || a_contains_b(b, a)
-}
-
-fn a_contains_b(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
-    a.contains(&b.start()) && a.contains(&b.end())
-}
-
-fn a_overlaps_b(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
-    a.end() >= b.start() && a.start() <= b.end()
+fn contained(a: &IntervalSet<usize>, b: &IntervalSet<usize>) -> bool {
+    a.is_subset(b) || b.is_subset(a)
 }
 
-fn overlap(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
-    a_overlaps_b(a, b) || a_overlaps_b(b, a)
+fn overlap(a: &IntervalSet<usize>, b: &IntervalSet<usize>) -> bool {
+    a.overlaps(b)
 }
 
 pub fn solve_part1(input: &str) -> String {
@@ -28,9 +18,12 @@ pub fn solve_part1(input: &str) -> String {
 
             let (start_second, end_second) = second.split_once("-").unwrap();
 
-            let first = start_first.parse::<usize>().unwrap()..=end_first.parse::<usize>().unwrap();
-            let second =
-                start_second.parse::<usize>().unwrap()..=end_second.parse::<usize>().unwrap();
+            let first = IntervalSet::from(
+                start_first.parse::<usize>().unwrap()..=end_first.parse::<usize>().unwrap(),
+            );
+            let second = IntervalSet::from(
+                start_second.parse::<usize>().unwrap()..=end_second.parse::<usize>().unwrap(),
+            );
 
             contained(&first, &second)
         })
@@ -48,9 +41,12 @@ pub fn solve_part2(input: &str) -> String {
 
             let (start_second, end_second) = second.split_once("-").unwrap();
 
-            let first = start_first.parse::<usize>().unwrap()..=end_first.parse::<usize>().unwrap();
-            let second =
-                start_second.parse::<usize>().unwrap()..=end_second.parse::<usize>().unwrap();
+            let first = IntervalSet::from(
+                start_first.parse::<usize>().unwrap()..=end_first.parse::<usize>().unwrap(),
+            );
+            let second = IntervalSet::from(
+                start_second.parse::<usize>().unwrap()..=end_second.parse::<usize>().unwrap(),
+            );
 
             overlap(&first, &second)
         })
@@ -0,0 +1,44 @@
+use day_07::filesystem::FileSystem;
+use std::{env, fs};
+
+const USAGE: &str =
+    "usage: report tree | du | find <name pattern> | find +<min size> | find -<max size>";
+
+/// Reports on the file system of `./input.txt`.
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let fs: FileSystem = match file.parse() {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("input.txt:{e}");
+            std::process::exit(1);
+        }
+    };
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["tree"] => print!("{fs}"),
+        ["du"] => {
+            for (path, size) in fs.du() {
+                println!("{size}\t{path}");
+            }
+        }
+        ["find", pattern] => {
+            let found = if let Some(Ok(min)) = pattern.strip_prefix('+').map(str::parse::<u64>) {
+                fs.find_by_size(min..)
+            } else if let Some(Ok(max)) = pattern.strip_prefix('-').map(str::parse::<u64>) {
+                fs.find_by_size(..=max)
+            } else {
+                fs.find_by_name(pattern)
+            };
+
+            found.iter().for_each(|path| println!("{path}"));
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use std::{collections::BTreeMap, fmt::Display, ops::RangeBounds, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    character::complete::{self, not_line_ending, space1},
+    combinator::{all_consuming, map},
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    Rm(&'a str),
+    Mv(&'a str, &'a str),
+    Dir(&'a str),
+    File(u64, &'a str),
+}
+
+fn name(input: &str) -> IResult<&str, &str> {
+    take_till1(|c: char| c.is_whitespace())(input)
+}
+
+fn line(input: &str) -> IResult<&str, Line<'_>> {
+    all_consuming(alt((
+        map(preceded(tag("$ cd "), not_line_ending), Line::Cd),
+        map(tag("$ ls"), |_| Line::Ls),
+        map(preceded(tag("$ rm "), name), Line::Rm),
+        map(
+            preceded(tag("$ mv "), separated_pair(name, space1, name)),
+            |(from, to)| Line::Mv(from, to),
+        ),
+        map(preceded(tag("dir "), name), Line::Dir),
+        map(
+            separated_pair(complete::u64, space1, name),
+            |(size, name)| Line::File(size, name),
+        ),
+    )))(input)
+}
+
+/// Why a terminal log can not have come from a real file system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    Malformed(String),
+    OutputWithoutLs,
+    AboveRoot,
+    UnknownDirectory(String),
+    NotADirectory(String),
+    UnknownEntry(String),
+    Conflict(String),
+    Exists(String),
+    Busy(String),
+    IntoItself(String),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Malformed(line) => write!(f, "can not read `{line}`"),
+            ErrorKind::OutputWithoutLs => write!(f, "listing without a `ls` before it"),
+            ErrorKind::AboveRoot => write!(f, "`..` goes above the root directory"),
+            ErrorKind::UnknownDirectory(path) => write!(f, "no directory `{path}`"),
+            ErrorKind::NotADirectory(path) => write!(f, "`{path}` is a file"),
+            ErrorKind::UnknownEntry(path) => write!(f, "no file or directory `{path}`"),
+            ErrorKind::Conflict(path) => write!(f, "`{path}` was listed differently before"),
+            ErrorKind::Exists(path) => write!(f, "`{path}` already exists"),
+            ErrorKind::Busy(path) => write!(f, "`{path}` holds the current directory"),
+            ErrorKind::IntoItself(path) => write!(f, "can not move `{path}` into itself"),
+        }
+    }
+}
+
+/// First line of the log, starting at 1, that does not add up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogError {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for LogError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    File(u64),
+    Dir(BTreeMap<String, Entry>),
+}
+
+impl Entry {
+    /// Size of a file, or of everything inside a directory.
+    pub fn size(&self) -> u64 {
+        match self {
+            Entry::File(size) => *size,
+            Entry::Dir(entries) => entries.values().map(Entry::size).sum(),
+        }
+    }
+}
+
+fn display(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+/// Directory names from the root, `path` is either absolute or relative to `cwd`.
+fn resolve(cwd: &[String], path: &str) -> Result<Vec<String>, ErrorKind> {
+    let mut res = if path.starts_with('/') {
+        vec![]
+    } else {
+        cwd.to_vec()
+    };
+
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                res.pop().ok_or(ErrorKind::AboveRoot)?;
+            }
+            part => res.push(part.to_string()),
+        }
+    }
+
+    Ok(res)
+}
+
+/// The directories and files seen while replaying a terminal log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    root: Entry,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem {
+            root: Entry::Dir(BTreeMap::new()),
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn get(&self, path: &[String]) -> Option<&Entry> {
+        path.iter().try_fold(&self.root, |entry, name| match entry {
+            Entry::Dir(entries) => entries.get(name),
+            Entry::File(_) => None,
+        })
+    }
+
+    fn dir_mut(&mut self, path: &[String]) -> Result<&mut BTreeMap<String, Entry>, ErrorKind> {
+        let mut entry = &mut self.root;
+
+        for (i, name) in path.iter().enumerate() {
+            let Entry::Dir(entries) = entry else {
+                return Err(ErrorKind::NotADirectory(display(&path[..i])));
+            };
+
+            entry = entries
+                .get_mut(name)
+                .ok_or_else(|| ErrorKind::UnknownDirectory(display(&path[..=i])))?;
+        }
+
+        match entry {
+            Entry::Dir(entries) => Ok(entries),
+            Entry::File(_) => Err(ErrorKind::NotADirectory(display(path))),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.root.size()
+    }
+
+    /// Lists `entry` under the directory `cwd`, it has to match anything listed there before.
+    fn list(&mut self, cwd: &[String], name: &str, entry: Entry) -> Result<(), ErrorKind> {
+        let entries = self.dir_mut(cwd)?;
+
+        match (entries.get(name), &entry) {
+            (None, _) => {
+                entries.insert(name.to_string(), entry);
+            }
+            (Some(Entry::Dir(_)), Entry::Dir(_)) => {}
+            (Some(old), new) if old == new => {}
+            _ => {
+                let path = [cwd, &[name.to_string()]].concat();
+                return Err(ErrorKind::Conflict(display(&path)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove(&mut self, cwd: &[String], path: &[String]) -> Result<Entry, ErrorKind> {
+        let Some((name, parent)) = path.split_last() else {
+            return Err(ErrorKind::Busy(display(path)));
+        };
+
+        if cwd.starts_with(path) {
+            return Err(ErrorKind::Busy(display(path)));
+        }
+
+        self.dir_mut(parent)
+            .ok()
+            .and_then(|entries| entries.remove(name))
+            .ok_or_else(|| ErrorKind::UnknownEntry(display(path)))
+    }
+
+    /// Moves `from` to `to`, or into `to` if it is a directory, like `mv` would.
+    fn rename(&mut self, cwd: &[String], from: &[String], to: &[String]) -> Result<(), ErrorKind> {
+        if self.get(from).is_none() {
+            return Err(ErrorKind::UnknownEntry(display(from)));
+        }
+
+        let to = match (self.get(to), from.last()) {
+            (Some(Entry::Dir(_)), Some(name)) => [to, std::slice::from_ref(name)].concat(),
+            _ => to.to_vec(),
+        };
+
+        if to.starts_with(from) {
+            return Err(ErrorKind::IntoItself(display(from)));
+        }
+
+        if self.get(&to).is_some() {
+            return Err(ErrorKind::Exists(display(&to)));
+        }
+
+        let Some((name, parent)) = to.split_last() else {
+            return Err(ErrorKind::Exists(display(&to)));
+        };
+
+        self.dir_mut(parent)?;
+
+        let entry = self.remove(cwd, from)?;
+        self.dir_mut(parent)?.insert(name.clone(), entry);
+
+        Ok(())
+    }
+
+    /// Replays a terminal log, starting at the root.
+    pub fn from_log(log: &str) -> Result<Self, LogError> {
+        let mut fs = FileSystem::default();
+        let mut cwd: Vec<String> = vec![];
+        let mut listing = false;
+
+        for (i, text) in log.lines().enumerate() {
+            let error = |kind| LogError { line: i + 1, kind };
+
+            let (_, line) =
+                line(text).map_err(|_| error(ErrorKind::Malformed(text.to_string())))?;
+
+            let res = match line {
+                Line::Cd(path) => resolve(&cwd, path).and_then(|path| {
+                    fs.dir_mut(&path)?;
+                    cwd = path;
+                    Ok(())
+                }),
+                Line::Ls => Ok(()),
+                Line::Rm(path) => resolve(&cwd, path).and_then(|path| {
+                    fs.remove(&cwd, &path)?;
+                    Ok(())
+                }),
+                Line::Mv(from, to) => resolve(&cwd, from)
+                    .and_then(|from| Ok((from, resolve(&cwd, to)?)))
+                    .and_then(|(from, to)| fs.rename(&cwd, &from, &to)),
+                Line::Dir(_) | Line::File(..) if !listing => Err(ErrorKind::OutputWithoutLs),
+                Line::Dir(name) => fs.list(&cwd, name, Entry::Dir(BTreeMap::new())),
+                Line::File(size, name) => fs.list(&cwd, name, Entry::File(size)),
+            };
+
+            res.map_err(error)?;
+
+            listing = matches!(line, Line::Ls | Line::Dir(_) | Line::File(..));
+        }
+
+        Ok(fs)
+    }
+
+    /// Every directory with its size, like `du`: the contents before the directory holding them.
+    pub fn du(&self) -> Vec<(String, u64)> {
+        fn visit(
+            entries: &BTreeMap<String, Entry>,
+            path: &str,
+            res: &mut Vec<(String, u64)>,
+        ) -> u64 {
+            let mut size = 0;
+
+            for (name, entry) in entries {
+                size += match entry {
+                    Entry::File(file) => *file,
+                    Entry::Dir(entries) => visit(entries, &format!("{path}/{name}"), res),
+                };
+            }
+
+            res.push((if path.is_empty() { "/" } else { path }.to_string(), size));
+
+            size
+        }
+
+        let mut res = vec![];
+
+        if let Entry::Dir(entries) = &self.root {
+            visit(entries, "", &mut res);
+        }
+
+        res
+    }
+
+    /// Paths of everything below the root, parents first, for which `pred` holds.
+    fn find(&self, pred: impl Fn(&str, &Entry) -> bool) -> Vec<String> {
+        fn visit(
+            entries: &BTreeMap<String, Entry>,
+            path: &str,
+            pred: &impl Fn(&str, &Entry) -> bool,
+            res: &mut Vec<String>,
+        ) {
+            for (name, entry) in entries {
+                let path = format!("{path}/{name}");
+
+                if pred(name, entry) {
+                    res.push(path.clone());
+                }
+
+                if let Entry::Dir(entries) = entry {
+                    visit(entries, &path, pred, res);
+                }
+            }
+        }
+
+        let mut res = vec![];
+
+        if let Entry::Dir(entries) = &self.root {
+            visit(entries, "", &pred, &mut res);
+        }
+
+        res
+    }
+
+    /// Files and directories whose name matches `pattern`, where `*` is any text and `?` any
+    /// character.
+    pub fn find_by_name(&self, pattern: &str) -> Vec<String> {
+        let pattern = pattern.chars().collect::<Vec<_>>();
+
+        self.find(|name, _| matches(&pattern, &name.chars().collect::<Vec<_>>()))
+    }
+
+    /// Files with a size in `sizes`.
+    pub fn find_by_size(&self, sizes: impl RangeBounds<u64>) -> Vec<String> {
+        self.find(|_, entry| matches!(entry, Entry::File(size) if sizes.contains(size)))
+    }
+}
+
+/// Glob match, `*` takes any run of characters and `?` exactly one.
+fn matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            matches(rest, name) || (!name.is_empty() && matches(pattern, &name[1..]))
+        }
+        (Some(('?', rest)), Some((_, name))) => matches(rest, name),
+        (Some((p, rest)), Some((c, name))) => p == c && matches(rest, name),
+        _ => false,
+    }
+}
+
+impl FromStr for FileSystem {
+    type Err = LogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FileSystem::from_log(s)
+    }
+}
+
+/// The listing of the puzzle, `- a (dir)` and `- b.txt (file, size=14848514)`.
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn visit(
+            f: &mut std::fmt::Formatter<'_>,
+            name: &str,
+            entry: &Entry,
+            depth: usize,
+        ) -> std::fmt::Result {
+            let indent = "  ".repeat(depth);
+
+            match entry {
+                Entry::File(size) => writeln!(f, "{indent}- {name} (file, size={size})"),
+                Entry::Dir(entries) => {
+                    writeln!(f, "{indent}- {name} (dir)")?;
+
+                    for (name, entry) in entries {
+                        visit(f, name, entry, depth + 1)?;
+                    }
+
+                    Ok(())
+                }
+            }
+        }
+
+        visit(f, "/", &self.root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    fn error(log: &str) -> LogError {
+        log.parse::<FileSystem>().unwrap_err()
+    }
+
+    #[test]
+    fn tree_test() {
+        let fs: FileSystem = LOG.parse().unwrap();
+
+        assert_eq!(
+            fs.to_string(),
+            "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+
+        assert_eq!(
+            fs.du(),
+            [
+                ("/a/e".to_string(), 584),
+                ("/a".to_string(), 94853),
+                ("/d".to_string(), 24933642),
+                ("/".to_string(), 48381165)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_test() {
+        let fs: FileSystem = LOG.parse().unwrap();
+
+        assert_eq!(
+            fs.find_by_name("*.*"),
+            ["/a/h.lst", "/b.txt", "/c.dat", "/d/d.ext", "/d/d.log"]
+        );
+        assert_eq!(fs.find_by_name("d*"), ["/d", "/d/d.ext", "/d/d.log"]);
+        assert_eq!(
+            fs.find_by_name("?"),
+            ["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/d", "/d/j", "/d/k"]
+        );
+        assert_eq!(fs.find_by_size(..3000), ["/a/e/i", "/a/g"]);
+        assert_eq!(
+            fs.find_by_size(8_000_000..),
+            ["/b.txt", "/c.dat", "/d/d.log"]
+        );
+    }
+
+    #[test]
+    fn commands_test() {
+        let fs: FileSystem = [
+            LOG,
+            "$ mv /b.txt /a/e",
+            "$ cd /",
+            "$ rm a/e/i",
+            "$ mv d/k a/z",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            fs.find_by_name("*"),
+            [
+                "/a",
+                "/a/e",
+                "/a/e/b.txt",
+                "/a/f",
+                "/a/g",
+                "/a/h.lst",
+                "/a/z",
+                "/c.dat",
+                "/d",
+                "/d/d.ext",
+                "/d/d.log",
+                "/d/j"
+            ]
+        );
+        assert_eq!(fs.size(), 48381165 - 584);
+    }
+
+    #[test]
+    fn errors_test() {
+        assert_eq!(
+            error("$ cd /\n$ cd a"),
+            LogError {
+                line: 2,
+                kind: ErrorKind::UnknownDirectory("/a".to_string())
+            }
+        );
+        assert_eq!(
+            error("$ cd /\n$ cd .."),
+            LogError {
+                line: 2,
+                kind: ErrorKind::AboveRoot
+            }
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n$ cd a").kind,
+            ErrorKind::NotADirectory("/a".to_string())
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n$ cd /\n2 b").kind,
+            ErrorKind::OutputWithoutLs
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n$ ls\n2 a").kind,
+            ErrorKind::Conflict("/a".to_string())
+        );
+        assert_eq!(
+            error("$ rm a").kind,
+            ErrorKind::UnknownEntry("/a".to_string())
+        );
+        assert_eq!(
+            error("$ ls\ndir a\n$ cd a\n$ rm /a").kind,
+            ErrorKind::Busy("/a".to_string())
+        );
+        assert_eq!(
+            error("$ ls\ndir a\n$ mv a a/b").kind,
+            ErrorKind::IntoItself("/a".to_string())
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n1 b\n$ mv a b").kind,
+            ErrorKind::Exists("/b".to_string())
+        );
+        assert_eq!(error("$ pwd").to_string(), "line 1: can not read `$ pwd`");
+    }
+}
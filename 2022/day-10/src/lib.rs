@@ -2,6 +2,7 @@
 
 use std::iter::zip;
 
+use aoc_ocr::OcrError;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete;
@@ -59,7 +60,8 @@ pub fn solve_part1(input: &str) -> String {
         .to_string()
 }
 
-pub fn solve_part2(input: &str) -> String {
+/// What the CRT shows, `#` for lit pixels and `.` for dark ones.
+pub fn render(input: &str) -> String {
     let (_, instructions) = parse(input).unwrap();
 
     const SCAN_LINE_LEN: usize = 40;
@@ -67,7 +69,7 @@ pub fn solve_part2(input: &str) -> String {
 
     zip(
         0..(SCAN_LINE_LEN * NUM_SCAN_LINES),
-        register_values(instructions).iter().skip(0),
+        register_values(instructions).iter(),
     )
     .map(|(pixel, reg)| (pixel % SCAN_LINE_LEN, reg))
     .map(|(pixel, (_, x_reg))| {
@@ -82,6 +84,10 @@ pub fn solve_part2(input: &str) -> String {
     .collect::<String>()
 }
 
+pub fn solve_part2(input: &str) -> Result<String, OcrError> {
+    aoc_ocr::read(&render(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,8 +112,8 @@ addx -5";
     }
 
     #[test]
-    fn part2_works() {
-        let result = solve_part2(INPUT);
+    fn render_works() {
+        let result = render(INPUT);
         assert_eq!(
             result,
             "##..##..##..##..##..##..##..##..##..##..
@@ -119,4 +125,20 @@ addx -5";
 "
         );
     }
+
+    #[test]
+    fn part2_works() {
+        let result = solve_part2(include_str!("../letters.txt")).unwrap();
+        assert_eq!(result, "RGZEHURK");
+    }
+
+    #[test]
+    fn part2_sample_fails() {
+        // the sample draws a test pattern, not letters
+        let result = solve_part2(INPUT);
+        assert!(matches!(
+            result,
+            Err(OcrError::UnknownGlyph { column: 0, .. })
+        ));
+    }
 }
@@ -1,7 +1,13 @@
-use advent_of_code_2022::solve_part2;
-use std::fs;
+use advent_of_code_2022::solve;
+use std::{env, fs};
 
+/// Plays 10000 rounds with no relief, unless told otherwise: `part-2 [rounds] [relief]`.
 fn main() {
+    let mut args = env::args().skip(1);
+
+    let rounds = args.next().map_or(10_000, |arg| arg.parse().unwrap());
+    let relief = args.next().unwrap_or("old".to_string());
+
     let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", solve_part2(&file));
+    println!("{}", solve(&file, rounds, &relief).unwrap());
 }
@@ -0,0 +1,183 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self, char, space0},
+    combinator::{all_consuming, map, value},
+    multi::fold_many0,
+    sequence::{delimited, pair},
+    IResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// A worry level computed from the `old` one, like `old * (old + 3)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Const(u64),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn has_division(&self) -> bool {
+        match self {
+            Expr::Binary(op, lhs, rhs) => {
+                *op == Op::Div || lhs.has_division() || rhs.has_division()
+            }
+            _ => false,
+        }
+    }
+
+    /// Exact value, `None` if it overflows, goes below zero or divides by zero.
+    pub fn eval(&self, old: u64) -> Option<u64> {
+        match self {
+            Expr::Old => Some(old),
+            Expr::Const(n) => Some(*n),
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(old)?, rhs.eval(old)?);
+
+                match op {
+                    Op::Add => lhs.checked_add(rhs),
+                    Op::Sub => lhs.checked_sub(rhs),
+                    Op::Mul => lhs.checked_mul(rhs),
+                    Op::Div => lhs.checked_div(rhs),
+                }
+            }
+        }
+    }
+
+    /// Value modulo `m`, `None` if there is a division as it does not carry over.
+    ///
+    /// Differences are taken modulo `m` as well, so one that goes below zero gives the
+    /// remainder of the negative value where [`Expr::eval`] fails.
+    pub fn eval_mod(&self, old: u64, m: u64) -> Option<u64> {
+        let m = m as u128;
+
+        let res = match self {
+            Expr::Old => old as u128 % m,
+            Expr::Const(n) => *n as u128 % m,
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.eval_mod(old, m as u64)? as u128;
+                let rhs = rhs.eval_mod(old, m as u64)? as u128;
+
+                match op {
+                    Op::Add => (lhs + rhs) % m,
+                    Op::Sub => (lhs + m - rhs) % m,
+                    Op::Mul => lhs * rhs % m,
+                    Op::Div => return None,
+                }
+            }
+        };
+
+        Some(res as u64)
+    }
+}
+
+fn binary(op: Op, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+}
+
+fn atom(s: &str) -> IResult<&str, Expr> {
+    delimited(
+        space0,
+        alt((
+            value(Expr::Old, tag("old")),
+            map(complete::u64, Expr::Const),
+            delimited(char('('), expr, char(')')),
+        )),
+        space0,
+    )(s)
+}
+
+fn term(s: &str) -> IResult<&str, Expr> {
+    let (s, first) = atom(s)?;
+
+    fold_many0(
+        pair(
+            alt((value(Op::Mul, char('*')), value(Op::Div, char('/')))),
+            atom,
+        ),
+        move || first.clone(),
+        |lhs, (op, rhs)| binary(op, lhs, rhs),
+    )(s)
+}
+
+/// Sums of products, both grouped from the left, so `old - 1 - 2` is `(old - 1) - 2`.
+fn expr(s: &str) -> IResult<&str, Expr> {
+    let (s, first) = term(s)?;
+
+    fold_many0(
+        pair(
+            alt((value(Op::Add, char('+')), value(Op::Sub, char('-')))),
+            term,
+        ),
+        move || first.clone(),
+        |lhs, (op, rhs)| binary(op, lhs, rhs),
+    )(s)
+}
+
+impl FromStr for Expr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        all_consuming(expr)(s)
+            .map(|(_, expr)| expr)
+            .map_err(|_| anyhow!("can not read the expression `{s}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str, old: u64) -> Option<u64> {
+        s.parse::<Expr>().unwrap().eval(old)
+    }
+
+    #[test]
+    fn eval_test() {
+        assert_eq!(eval("old * 19", 79), Some(1501));
+        assert_eq!(eval("old*old", 7), Some(49));
+        assert_eq!(eval("2 + old * 3", 5), Some(17));
+        assert_eq!(eval("(2 + old) * 3", 5), Some(21));
+        assert_eq!(eval("old - 1 - 2", 5), Some(2));
+        assert_eq!(eval("old / 3", 1501), Some(500));
+        assert_eq!(eval("old / (old - 1)", 1), None);
+        assert_eq!(eval("old - 6", 5), None);
+
+        assert!("old +".parse::<Expr>().is_err());
+        assert!("(old".parse::<Expr>().is_err());
+        assert!("new".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn eval_mod_test() {
+        let e: Expr = "(old + 4) * old + 9".parse().unwrap();
+
+        for old in 0..100 {
+            assert_eq!(e.eval_mod(old, 23), e.eval(old).map(|v| v % 23));
+        }
+
+        assert!(!e.has_division());
+        assert_eq!("old / 2".parse::<Expr>().unwrap().eval_mod(4, 23), None);
+
+        // below zero, the remainder of the negative value
+        let e: Expr = "old * old - 30".parse().unwrap();
+
+        for old in 0..100 {
+            let value = (old * old) as i64 - 30;
+
+            assert_eq!(e.eval_mod(old, 23), Some(value.rem_euclid(23) as u64));
+            assert_eq!(e.eval(old), u64::try_from(value).ok());
+        }
+    }
+}
@@ -1,73 +1,51 @@
-use std::convert::TryFrom;
+pub mod expr;
 
-use anyhow::*;
+use std::str::FromStr;
 
-#[derive(Debug)]
-struct Monkey<'a> {
-    items: Vec<u128>,
-    num_inspected: u128,
-    operation: (&'a str, &'a str),
-    test: u128,
-    next_if_true: usize,
-    next_if_false: usize,
+use anyhow::*;
+use expr::Expr;
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub items: Vec<u64>,
+    pub operation: Expr,
+    pub test: u64,
+    pub next_if_true: usize,
+    pub next_if_false: usize,
 }
 
-#[derive(Debug)]
-struct Monkeys<'a>(Vec<Monkey<'a>>);
-
-impl<'a> TryFrom<&'a str> for Monkey<'a> {
-    type Error = anyhow::Error;
-
-    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
-        let (_, s) = s.split_once('\n').unwrap();
-
-        let (items, s) = s.split_once('\n').unwrap();
-
-        let items = items.strip_prefix("  Starting items: ").unwrap();
-
-        let mut items = items
-            .split(", ")
-            .filter_map(|s| s.parse::<_>().ok())
-            .collect::<Vec<_>>();
-
-        items.reverse();
-
-        let (operation, s) = s.split_once('\n').unwrap();
-
-        let mut operation = operation
-            .strip_prefix("  Operation: new = old ")
-            .unwrap()
-            .split(' ')
-            .collect::<Vec<_>>();
-
-        let argument = operation.pop().unwrap();
+/// What follows `prefix` on the next line.
+fn field<'a>(lines: &mut impl Iterator<Item = &'a str>, prefix: &str) -> Result<&'a str> {
+    lines
+        .next()
+        .and_then(|line| line.trim().strip_prefix(prefix))
+        .with_context(|| format!("expected a line starting with `{prefix}`"))
+}
 
-        let symbol = operation.pop().unwrap();
+impl FromStr for Monkey {
+    type Err = anyhow::Error;
 
-        let operation = (argument, symbol);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
 
-        let (test, s) = s.split_once('\n').unwrap();
+        field(&mut lines, "Monkey ")?;
 
-        let test = test
-            .strip_prefix("  Test: divisible by ")
-            .unwrap()
-            .parse::<_>()?;
+        let items = field(&mut lines, "Starting items:")?
+            .split(',')
+            .filter(|item| !item.trim().is_empty())
+            .map(|item| item.trim().parse().context("bad starting item"))
+            .collect::<Result<_>>()?;
 
-        let (next_if_true, s) = s.split_once('\n').unwrap();
+        let operation = field(&mut lines, "Operation: new =")?.parse()?;
 
-        let next_if_true = next_if_true
-            .strip_prefix("    If true: throw to monkey ")
-            .unwrap()
-            .parse::<usize>()?;
+        let test = field(&mut lines, "Test: divisible by ")?.parse()?;
+        ensure!(test > 0, "can not test divisibility by 0");
 
-        let next_if_false = s
-            .strip_prefix("    If false: throw to monkey ")
-            .unwrap()
-            .parse::<usize>()?;
+        let next_if_true = field(&mut lines, "If true: throw to monkey ")?.parse()?;
+        let next_if_false = field(&mut lines, "If false: throw to monkey ")?.parse()?;
 
         Ok(Monkey {
             items,
-            num_inspected: 0,
             operation,
             test,
             next_if_true,
@@ -76,89 +54,138 @@ impl<'a> TryFrom<&'a str> for Monkey<'a> {
     }
 }
 
-impl<'a> Monkeys<'a> {
-    fn new_from_str(input: &str) -> Monkeys {
-        Monkeys(
-            input
-                .split("\n\n")
-                .map(|block| block.try_into().unwrap())
-                .collect(),
-        )
+/// Items inspected by each monkey, round after round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspections {
+    pub rounds: Vec<Vec<u64>>,
+}
+
+impl Inspections {
+    pub fn totals(&self) -> Vec<u64> {
+        self.rounds.iter().fold(vec![], |mut totals, round| {
+            totals.resize(round.len(), 0);
+            totals.iter_mut().zip(round).for_each(|(t, n)| *t += n);
+            totals
+        })
     }
 
-    fn round(&mut self, worry_factor: u128) {
-        for i in 0..self.0.len() {
-            let monkey = self.0.get_mut(i).unwrap();
+    pub fn monkey_business(&self) -> u64 {
+        let mut totals = self.totals();
+        totals.sort();
+
+        totals.iter().rev().take(2).product()
+    }
+}
 
-            let distribution_items = monkey
-                .items
-                .iter()
-                .map(|item| {
-                    monkey.num_inspected += 1;
+#[derive(Debug, Clone)]
+pub struct Monkeys(pub Vec<Monkey>);
+
+impl FromStr for Monkeys {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let monkeys = s
+            .split("\n\n")
+            .enumerate()
+            .map(|(i, block)| block.parse().with_context(|| format!("monkey {i}")))
+            .collect::<Result<Vec<Monkey>>>()?;
+
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for next in [monkey.next_if_true, monkey.next_if_false] {
+                ensure!(
+                    next < monkeys.len(),
+                    "monkey {i} throws to missing monkey {next}"
+                );
+            }
+        }
 
-                    let argument = match monkey.operation.0 {
-                        "old" => *item,
-                        _ => monkey.operation.0.parse::<_>().unwrap(),
-                    };
+        Ok(Monkeys(monkeys))
+    }
+}
 
-                    let item = item % (11 * 2 * 5 * 7 * 17 * 19 * 3 * 13 * 19 * 23);
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
 
-                    let item = match monkey.operation.1 {
-                        "*" => item * argument,
-                        "+" => item + argument,
-                        _ => unreachable!(),
-                    } / worry_factor;
+impl Monkeys {
+    /// Product of all the test divisors, or rather their least common multiple. Worry levels
+    /// taken modulo it are still divisible by the same tests.
+    pub fn modulus(&self) -> Result<u64> {
+        self.0.iter().try_fold(1_u64, |acc, monkey| {
+            (acc / gcd(acc, monkey.test))
+                .checked_mul(monkey.test)
+                .context("the test divisors have no common multiple that fits in a u64")
+        })
+    }
 
-                    if item % monkey.test == 0 {
-                        (monkey.next_if_true, item)
-                    } else {
-                        (monkey.next_if_false, item)
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            monkey.items.clear();
-
-            for distribution in distribution_items {
-                self.0
-                    .get_mut(distribution.0)
-                    .unwrap()
-                    .items
-                    .push(distribution.1);
+    /// Plays `rounds` rounds, applying `relief` to each worry level after the operation.
+    ///
+    /// Levels are kept modulo [`Monkeys::modulus`], unless something divides and they have to
+    /// be exact. Only exact levels fail when they go below zero.
+    pub fn simulate(&self, rounds: usize, relief: &Expr) -> Result<Inspections> {
+        let exact = relief.has_division() || self.0.iter().any(|m| m.operation.has_division());
+        let modulus = if exact { 0 } else { self.modulus()? };
+
+        let eval = |expr: &Expr, old| {
+            if exact {
+                expr.eval(old)
+            } else {
+                expr.eval_mod(old, modulus)
             }
-        }
-    }
+        };
 
-    fn monkey_business(&self) -> u128 {
-        let mut num_inspected = self
-            .0
-            .iter()
-            .map(|monkey| monkey.num_inspected)
-            .collect::<Vec<_>>();
+        let mut items = self.0.iter().map(|m| m.items.clone()).collect::<Vec<_>>();
+        let mut res = vec![];
 
-        num_inspected.sort();
+        for round in 1..=rounds {
+            let mut inspected = vec![0; self.0.len()];
 
-        num_inspected.iter().rev().take(2).product()
-    }
-}
+            for (i, monkey) in self.0.iter().enumerate() {
+                for old in std::mem::take(&mut items[i]) {
+                    inspected[i] += 1;
 
-pub fn solve_part1(input: &str) -> String {
-    let mut monkeys = Monkeys::new_from_str(input);
+                    let worry = eval(&monkey.operation, old)
+                        .and_then(|new| eval(relief, new))
+                        .with_context(|| {
+                            format!("round {round}: monkey {i} can not handle worry level {old}")
+                        })?;
 
-    for _ in 1..=20 {
-        monkeys.round(3);
+                    let next = if worry % monkey.test == 0 {
+                        monkey.next_if_true
+                    } else {
+                        monkey.next_if_false
+                    };
+
+                    items[next].push(worry);
+                }
+            }
+
+            res.push(inspected);
+        }
+
+        Ok(Inspections { rounds: res })
     }
-    monkeys.monkey_business().to_string()
 }
 
-pub fn solve_part2(input: &str) -> String {
-    let mut monkeys = Monkeys::new_from_str(input);
+/// Monkey business after `rounds` rounds, with worry levels going through `relief` each time.
+pub fn solve(input: &str, rounds: usize, relief: &str) -> Result<String> {
+    let monkeys: Monkeys = input.parse()?;
 
-    for _ in 1..=10_000 {
-        monkeys.round(1);
-    }
+    let inspections = monkeys.simulate(rounds, &relief.parse()?)?;
 
-    monkeys.monkey_business().to_string()
+    Ok(inspections.monkey_business().to_string())
+}
+
+pub fn solve_part1(input: &str) -> Result<String> {
+    solve(input, 20, "old / 3")
+}
+
+pub fn solve_part2(input: &str) -> Result<String> {
+    solve(input, 10_000, "old")
 }
 
 #[cfg(test)]
@@ -169,13 +196,56 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = solve_part1(INPUT);
+        let result = solve_part1(INPUT).unwrap();
         assert_eq!(result, "10605");
     }
 
     #[test]
     fn part2_works() {
-        let result = solve_part2(INPUT);
+        let result = solve_part2(INPUT).unwrap();
         assert_eq!(result, "2713310158");
     }
+
+    #[test]
+    fn inspections_works() {
+        let monkeys: Monkeys = INPUT.parse().unwrap();
+
+        assert_eq!(monkeys.modulus().unwrap(), 23 * 19 * 13 * 17);
+
+        let inspections = monkeys.simulate(20, &Expr::Old).unwrap();
+
+        assert_eq!(inspections.rounds[0], [2, 4, 3, 6]);
+        assert_eq!(inspections.totals(), [99, 97, 8, 103]);
+
+        // exact levels get too large for the squaring monkey
+        assert!(monkeys.simulate(20, &"old / 1".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn modulus_works() {
+        let mut monkeys: Monkeys = INPUT.parse().unwrap();
+
+        monkeys.0[0].test = 1 << 32;
+        monkeys.0[1].test = (1 << 33) - 1;
+
+        assert!(monkeys.modulus().is_err());
+        assert!(monkeys.simulate(1, &Expr::Old).is_err());
+
+        // exact levels do not need it
+        assert!(monkeys.simulate(1, &"old / 3".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn subtraction_works() {
+        let mut monkeys: Monkeys = INPUT.parse().unwrap();
+
+        // the squaring monkey, levels stay modulo the tests however many rounds
+        monkeys.0[2].operation = "old * old - 3".parse().unwrap();
+
+        let inspections = monkeys.simulate(10_000, &Expr::Old).unwrap();
+        assert_eq!(inspections.rounds.len(), 10_000);
+
+        // exact levels overflow after a few rounds instead
+        assert!(monkeys.simulate(20, &"old / 1".parse().unwrap()).is_err());
+    }
 }
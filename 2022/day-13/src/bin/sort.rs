@@ -0,0 +1,26 @@
+use advent_of_code_2022::packet::{distinct, sorted};
+use std::{env, fs};
+
+/// Prints the packets of a file in order, `sort [--unique] [file]`.
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+
+    let unique = args.next_if(|arg| arg == "--unique").is_some();
+    let path = args.next().unwrap_or("./input.txt".to_string());
+
+    let file = fs::read_to_string(&path).unwrap();
+
+    let packets = if unique {
+        distinct(&file)
+    } else {
+        sorted(&file)
+    };
+
+    match packets {
+        Ok(packets) => packets.iter().for_each(|p| println!("{p}")),
+        Err(e) => {
+            eprintln!("{path}:{e}");
+            std::process::exit(1);
+        }
+    }
+}
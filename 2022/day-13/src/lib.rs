@@ -1,110 +1,32 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, separated_pair},
-    *,
-};
+pub mod packet;
 
-#[derive(Debug)]
-enum Packet {
-    Int(u8),
-    List(Vec<Packet>),
-}
-
-impl PartialEq for Packet {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Int(n), Self::Int(m)) => n == m,
-            (Self::List(l), Self::List(r)) => l == r,
-            (Self::Int(n), Self::List(l)) => *l == vec![Self::Int(*n)],
-            (Self::List(l), Self::Int(n)) => *l == vec![Self::Int(*n)],
-        }
-    }
-}
-
-impl Eq for Packet {}
-
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match (self, other) {
-            (Self::Int(n), Self::Int(m)) => n.cmp(m),
-            (Self::List(l), Self::List(r)) => l.cmp(r),
-            (Self::Int(n), Self::List(l)) => vec![Self::Int(*n)].cmp(l),
-            (Self::List(l), Self::Int(n)) => l.cmp(&vec![Self::Int(*n)]),
-        }
-    }
-}
-
-impl PartialOrd for Packet {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn packet(s: &str) -> IResult<&str, Packet> {
-    alt((
-        complete::u8.map(Packet::Int),
-        delimited(tag("["), separated_list0(tag(","), packet), tag("]")).map(Packet::List),
-    ))(s)
-}
-
-fn packet_pair(s: &str) -> IResult<&str, (Packet, Packet)> {
-    separated_pair(packet, newline, packet)(s)
-}
-
-fn packet_pairs_list(s: &str) -> IResult<&str, Vec<(Packet, Packet)>> {
-    separated_list1(tag("\n\n"), packet_pair)(s)
-}
+use packet::{parse_packets, parse_pairs, Packet, ParseError};
 
-pub fn solve_part1(input: &str) -> String {
-    packet_pairs_list(input)
-        .unwrap()
-        .1
+pub fn solve_part1(input: &str) -> Result<String, ParseError> {
+    let sum = parse_pairs(input)?
         .iter()
         .enumerate()
-        .map(|(p, v)| (p + 1, v))
-        .filter_map(|(p, (left, right))| match left.cmp(right) {
-            std::cmp::Ordering::Less => Some(p),
-            std::cmp::Ordering::Equal => unreachable!(),
-            std::cmp::Ordering::Greater => None,
-        })
-        .sum::<usize>()
-        .to_string()
-}
-
-pub fn solve_part2(input: &str) -> String {
-    let packet_paris = packet_pairs_list(input).unwrap().1;
-
-    let mut packet_list = packet_paris
-        .iter()
-        .flat_map(|(left, right)| [left, right])
-        .collect::<Vec<_>>();
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
+        .sum::<usize>();
 
-    let (_, divider_1) = packet("[[2]]").unwrap();
-    let (_, divider_2) = packet("[[6]]").unwrap();
+    Ok(sum.to_string())
+}
 
-    packet_list.push(&divider_1);
-    packet_list.push(&divider_2);
+pub fn solve_part2(input: &str) -> Result<String, ParseError> {
+    let mut packets = parse_packets(input)?;
 
-    packet_list.sort();
+    let dividers = [Packet::from([[2_u64]]), Packet::from([[6_u64]])];
 
-    let (index_1, _) = packet_list
-        .iter()
-        .enumerate()
-        .map(|(i, p)| (i + 1, p))
-        .find(|(_, &p)| p == &divider_1)
-        .unwrap();
+    packets.extend(dividers.clone());
+    packets.sort();
 
-    let (index_2, _) = packet_list
+    let key = dividers
         .iter()
-        .enumerate()
-        .map(|(i, p)| (i + 1, p))
-        .find(|(_, &p)| p == &divider_2)
-        .unwrap();
+        .map(|divider| packets.partition_point(|p| p < divider) + 1)
+        .product::<usize>();
 
-    (index_1 * index_2).to_string()
+    Ok(key.to_string())
 }
 
 #[cfg(test)]
@@ -115,13 +37,20 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = solve_part1(INPUT);
+        let result = solve_part1(INPUT).unwrap();
         assert_eq!(result, "13");
     }
 
     #[test]
     fn part2_works() {
-        let result = solve_part2(INPUT);
+        let result = solve_part2(INPUT).unwrap();
         assert_eq!(result, "140");
     }
+
+    #[test]
+    fn part2_equal_packets_works() {
+        // `[2]` and `6` compare equal to the dividers, the first of the equal ones counts
+        let result = solve_part2("[2]\n[[1]]\n\n6\n[[[6]]]").unwrap();
+        assert_eq!(result, (2 * 4).to_string());
+    }
 }
@@ -0,0 +1,302 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete,
+    combinator::{cut, opt},
+    multi::many0,
+    sequence::{preceded, terminated},
+    Err, IResult, Parser,
+};
+
+/// An integer or a list of packets, compared the way the distress signal wants.
+///
+/// An integer next to a list compares, and equals, as the list holding only that integer.
+#[derive(Debug, Clone)]
+pub enum Packet {
+    Int(u64),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    pub fn as_int(&self) -> Option<u64> {
+        match self {
+            Packet::Int(n) => Some(*n),
+            Packet::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Packet]> {
+        match self {
+            Packet::Int(_) => None,
+            Packet::List(l) => Some(l),
+        }
+    }
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Int(n), Self::Int(m)) => n.cmp(m),
+            (Self::List(l), Self::List(r)) => l.cmp(r),
+            (Self::Int(n), Self::List(l)) => [Self::Int(*n)].as_slice().cmp(l),
+            (Self::List(l), Self::Int(n)) => l.as_slice().cmp(&[Self::Int(*n)]),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Same text as the input, `[1,[2,3]]`.
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Int(n) => write!(f, "{n}"),
+            Packet::List(l) => {
+                write!(f, "[")?;
+
+                for (i, p) in l.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{p}")?;
+                }
+
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl From<u64> for Packet {
+    fn from(n: u64) -> Self {
+        Packet::Int(n)
+    }
+}
+
+/// Nested vectors of integers, `vec![vec![1], vec![2, 3]]` is `[[1],[2,3]]`.
+impl<T: Into<Packet>> From<Vec<T>> for Packet {
+    fn from(l: Vec<T>) -> Self {
+        Packet::List(l.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Packet>, const N: usize> From<[T; N]> for Packet {
+    fn from(l: [T; N]) -> Self {
+        Packet::List(l.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Gives back the packet that is not an integer.
+impl TryFrom<Packet> for u64 {
+    type Error = Packet;
+
+    fn try_from(p: Packet) -> Result<Self, Self::Error> {
+        match p {
+            Packet::Int(n) => Ok(n),
+            p => Err(p),
+        }
+    }
+}
+
+/// Gives back the first packet, at any depth, that does not have the shape of `T`.
+impl<T: TryFrom<Packet, Error = Packet>> TryFrom<Packet> for Vec<T> {
+    type Error = Packet;
+
+    fn try_from(p: Packet) -> Result<Self, Self::Error> {
+        match p {
+            Packet::List(l) => l.into_iter().map(T::try_from).collect(),
+            p => Err(p),
+        }
+    }
+}
+
+/// Where a packet stops making sense, lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub found: Option<char>,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+
+        match self.found {
+            Some(c) => write!(f, "unexpected `{c}`"),
+            None => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn packet(s: &str) -> IResult<&str, Packet> {
+    alt((
+        complete::u64.map(Packet::Int),
+        preceded(tag("["), cut(terminated(items, tag("]")))).map(Packet::List),
+    ))(s)
+}
+
+/// Packets between commas, once there is a comma another packet has to follow.
+fn items(s: &str) -> IResult<&str, Vec<Packet>> {
+    let (s, Some(first)) = opt(packet)(s)? else {
+        return Ok((s, vec![]));
+    };
+
+    let (s, rest) = many0(preceded(tag(","), cut(packet)))(s)?;
+
+    Ok((s, std::iter::once(first).chain(rest).collect()))
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = match packet(s) {
+            Ok(("", p)) => return Ok(p),
+            Ok((rest, _)) => rest,
+            Err(Err::Error(e) | Err::Failure(e)) => e.input,
+            Err(Err::Incomplete(_)) => "",
+        };
+
+        let column = s[..s.len() - rest.len()].chars().count() + 1;
+
+        Err(ParseError {
+            line: 1,
+            column,
+            found: rest.chars().next(),
+        })
+    }
+}
+
+/// Every packet in the input, one per line, skipping blank lines.
+pub fn parse_packets(input: &str) -> Result<Vec<Packet>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|e: ParseError| ParseError { line: i + 1, ..e })
+        })
+        .collect()
+}
+
+/// Packets taken two by two, a missing last one is an error at the end of the input.
+pub fn parse_pairs(input: &str) -> Result<Vec<(Packet, Packet)>, ParseError> {
+    let mut packets = parse_packets(input)?.into_iter();
+    let mut pairs = vec![];
+
+    while let Some(left) = packets.next() {
+        let Some(right) = packets.next() else {
+            return Err(ParseError {
+                line: input.lines().count() + 1,
+                column: 1,
+                found: None,
+            });
+        };
+
+        pairs.push((left, right));
+    }
+
+    Ok(pairs)
+}
+
+/// Every packet of the input in order.
+pub fn sorted(input: &str) -> Result<Vec<Packet>, ParseError> {
+    let mut packets = parse_packets(input)?;
+    packets.sort();
+
+    Ok(packets)
+}
+
+/// Packets of the input in order, keeping one of each that compare equal.
+pub fn distinct(input: &str) -> Result<Vec<Packet>, ParseError> {
+    let mut packets = sorted(input)?;
+    packets.dedup();
+
+    Ok(packets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        for s in [
+            "[]",
+            "[[]]",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[[4,4],4,4]",
+            "10",
+        ] {
+            assert_eq!(s.parse::<Packet>().unwrap().to_string(), s);
+        }
+
+        let p = Packet::from(vec![vec![1_u64], vec![2, 3]]);
+        assert_eq!(p.to_string(), "[[1],[2,3]]");
+        assert_eq!(Vec::<Vec<u64>>::try_from(p), Ok(vec![vec![1], vec![2, 3]]));
+
+        // the integer where a list should be
+        let p: Packet = "[[1],2]".parse().unwrap();
+        assert_eq!(Vec::<Vec<u64>>::try_from(p).unwrap_err().as_int(), Some(2));
+    }
+
+    #[test]
+    fn order_test() {
+        let p = |s: &str| s.parse::<Packet>().unwrap();
+
+        assert!(p("[1,1,3,1,1]") < p("[1,1,5,1,1]"));
+        assert!(p("[[1],[2,3,4]]") < p("[[1],4]"));
+        assert!(p("[[[]]]") > p("[[]]"));
+        assert_eq!(p("[[2]]"), p("2"));
+
+        assert_eq!(
+            distinct("[[2]]\n[3]\n\n2\n[]\n[2]")
+                .unwrap()
+                .iter()
+                .map(Packet::to_string)
+                .collect::<Vec<_>>(),
+            ["[]", "[[2]]", "[3]"]
+        );
+    }
+
+    #[test]
+    fn errors_test() {
+        let error = |s: &str| s.parse::<Packet>().unwrap_err();
+
+        assert_eq!(
+            error("[1,x]"),
+            ParseError {
+                line: 1,
+                column: 4,
+                found: Some('x')
+            }
+        );
+        assert_eq!(error("[1,]").found, Some(']'));
+        assert_eq!(error("[1 2]").column, 3);
+        assert_eq!(error("[[1]").found, None);
+        assert_eq!(error("[1]]").to_string(), "1:4: unexpected `]`");
+
+        let e = parse_pairs("[1]\n[2]\n\n[3]\n[[4]\n").unwrap_err();
+        assert_eq!((e.line, e.column), (5, 5));
+
+        assert_eq!(parse_pairs("[1]\n[2]\n\n[3]").unwrap_err().line, 5);
+    }
+}
@@ -0,0 +1,25 @@
+use advent_of_code_2022::frames;
+use aoc_render::{save_png, Animation, Palette, GRAY, SAND, WHITE};
+use std::{env, fs};
+
+// usage: animate [keep one frame every]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let every = env::args().nth(1).map(|n| n.parse().unwrap()).unwrap_or(1);
+
+    let palette = Palette::new(WHITE).tile('#', GRAY).tile('o', SAND);
+
+    let mut animation = Animation::new(palette.clone(), 4).delay(2).every(every);
+    let mut last = String::new();
+
+    for frame in frames(&file) {
+        animation.push(&frame);
+        last = frame;
+    }
+
+    animation.save_gif("sand.gif").unwrap();
+    save_png("sand.png", &last, &palette, 4).unwrap();
+
+    println!("{} frames saved to sand.gif", animation.len());
+}
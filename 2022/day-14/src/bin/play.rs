@@ -0,0 +1,15 @@
+use advent_of_code_2022::frames;
+use aoc_render::terminal::Player;
+use std::{env, fs};
+
+// usage: play [frames per second]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let fps = env::args()
+        .nth(1)
+        .map(|n| n.parse().unwrap())
+        .unwrap_or(60.0);
+
+    Player::new(fps).play(frames(&file)).unwrap();
+}
@@ -12,7 +12,6 @@ use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum Block {
-    Air,
     Rock,
     Sand,
 }
@@ -29,7 +28,6 @@ struct Map {
     layout: HashMap<Coords, Block>,
     min_width: u32,
     max_width: u32,
-    min_height: u32,
     max_height: u32,
 }
 
@@ -62,7 +60,6 @@ impl Map {
 
         let r = v
             .windows(2)
-            .into_iter()
             .flat_map(|w| {
                 let h = cmp::min(w[0].x, w[1].x)..=cmp::max(w[0].x, w[1].x);
                 let v = cmp::min(w[0].y, w[1].y)..=cmp::max(w[0].y, w[1].y);
@@ -92,7 +89,6 @@ impl Map {
 
         let min_width = *keys.iter().map(|Coords { x, y: _ }| x).min().unwrap();
         let max_width = *keys.iter().map(|Coords { x, y: _ }| x).max().unwrap();
-        let min_height = *keys.iter().map(|Coords { x: _, y }| y).min().unwrap();
         let max_height = *keys.iter().map(|Coords { x: _, y }| y).max().unwrap();
 
         Map {
@@ -100,7 +96,6 @@ impl Map {
 
             min_width,
             max_width,
-            min_height,
             max_height,
         }
     }
@@ -113,14 +108,14 @@ impl Map {
         for coords in keys {
             let block = self.layout.get(&coords);
 
-            let Some(Sand) = block  else { continue };
+            let Some(Sand) = block else { continue };
 
             let down = Coords {
                 x: coords.x,
                 y: coords.y + 1,
             };
 
-            if self.layout.get(&down).is_none() {
+            if !self.layout.contains_key(&down) {
                 self.layout.remove(&coords);
                 self.layout.insert(down, Sand);
                 is_stable = false;
@@ -132,7 +127,7 @@ impl Map {
                 y: coords.y + 1,
             };
 
-            if self.layout.get(&left).is_none() {
+            if !self.layout.contains_key(&left) {
                 self.layout.remove(&coords);
                 self.layout.insert(left, Sand);
                 is_stable = false;
@@ -143,7 +138,7 @@ impl Map {
                 x: coords.x + 1,
                 y: coords.y + 1,
             };
-            if self.layout.get(&right).is_none() {
+            if !self.layout.contains_key(&right) {
                 self.layout.remove(&coords);
                 self.layout.insert(right, Sand);
                 is_stable = false;
@@ -155,14 +150,15 @@ impl Map {
     }
 
     fn is_over(&self) -> bool {
-        let max_width = *self
+        // sand below the lowest rock falls forever
+        let max_height = *self
             .layout
             .keys()
             .map(|Coords { x: _, y }| y)
             .max()
             .unwrap();
 
-        max_width > self.max_width
+        max_height > self.max_height
     }
 
     fn pop(&mut self) {
@@ -196,6 +192,27 @@ pub fn solve_part1(input: &str) -> String {
     .to_string()
 }
 
+/// Cave after every tick of part 1, from the first grain until sand flows into the abyss.
+pub fn frames(input: &str) -> impl Iterator<Item = String> {
+    let mut m = Map::from_str(input);
+
+    m.pop();
+
+    let first = format!("{:?}", m);
+
+    std::iter::once(first).chain(std::iter::from_fn(move || {
+        if m.is_over() {
+            return None;
+        }
+
+        if m.tick() {
+            m.pop();
+        }
+
+        Some(format!("{:?}", m))
+    }))
+}
+
 pub fn solve_part2(input: &str) -> String {
     let mut m = Map::from_str(input);
 
@@ -222,27 +239,21 @@ pub fn solve_part2(input: &str) -> String {
                 y: coords.y + 1,
             };
 
-            if m.layout.get(&left).is_none() {
-                m.layout.insert(left, Sand);
-            }
+            m.layout.entry(left).or_insert(Sand);
 
             let down = Coords {
                 x: coords.x,
                 y: coords.y + 1,
             };
 
-            if m.layout.get(&down).is_none() {
-                m.layout.insert(down, Sand);
-            }
+            m.layout.entry(down).or_insert(Sand);
 
             let right = Coords {
                 x: coords.x + 1,
                 y: coords.y + 1,
             };
 
-            if m.layout.get(&right).is_none() {
-                m.layout.insert(right, Sand);
-            }
+            m.layout.entry(right).or_insert(Sand);
         }
     }
 
@@ -272,4 +283,13 @@ mod tests {
         let result = solve_part2(INPUT);
         assert_eq!(result, "93");
     }
+
+    #[test]
+    fn frames_works() {
+        let frames = frames(INPUT).collect::<Vec<_>>();
+
+        assert!(frames.iter().all(|frame| frame.len() == frames[0].len()));
+        // resting sand and the grain falling into the abyss
+        assert_eq!(frames.last().unwrap().matches('o').count(), 24 + 1);
+    }
 }
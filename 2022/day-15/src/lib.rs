@@ -1,4 +1,9 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
+
+use aoc_intervals::IntervalSet;
+use region::{Rect, Region};
+
+pub mod region;
 
 use nom::{
     bytes::complete::tag,
@@ -56,6 +61,17 @@ fn file(s: &str) -> IResult<&str, Vec<Pair>> {
     separated_list1(newline, line)(s)
 }
 
+/// Positions of the row that are closer to some sensor than its beacon.
+fn coverage(map: &[Pair], row: i64) -> IntervalSet<i64> {
+    map.iter()
+        .filter_map(|Pair { sensor, beacon }| {
+            let dy = sensor.abs(*beacon) - (sensor.y - row).abs();
+
+            (dy >= 0).then(|| sensor.x - dy..=sensor.x + dy)
+        })
+        .collect()
+}
+
 pub fn solve_part1(input: &str, row: i64) -> String {
     let (_, map) = file(input).unwrap();
 
@@ -63,106 +79,33 @@ pub fn solve_part1(input: &str, row: i64) -> String {
         .iter()
         .filter_map(|Pair { beacon, .. }| if beacon.y == row { Some(beacon) } else { None })
         .collect::<HashSet<_>>()
-        .len();
-
-    let mut intervals = Vec::<(i64, i64)>::new();
-
-    for Pair { sensor, beacon } in map.iter() {
-        let distance_to_row = (sensor.y - row).abs();
-
-        let distance_to_beacon = sensor.abs(*beacon);
-
-        if distance_to_row > distance_to_beacon {
-            continue;
-        };
-
-        let dy = distance_to_beacon - distance_to_row;
-
-        let interval = (sensor.x - dy, sensor.x + dy);
-
-        intervals.push(interval)
-    }
-
-    intervals.sort();
-
-    let mut intervals = intervals.iter().copied().collect::<VecDeque<_>>();
-
-    let mut stack = vec![intervals.pop_front().unwrap()];
-
-    for interval in intervals {
-        let mut top = stack.pop().unwrap();
-
-        if (top.1 + 1) < interval.0 {
-            stack.push(interval);
-        } else {
-            top.1 = top.1.max(interval.1);
-            stack.push(top);
-        }
-    }
-
-    let s = stack
-        .iter()
-        .fold(0, |acc, interval| acc + interval.1 - interval.0 + 1) as usize;
+        .len() as i64;
 
-    (s - b).to_string()
+    (coverage(&map, row).total_len() - b).to_string()
 }
 
-pub fn solve_part2(input: &str, size: i64) -> String {
+/// Every cell from `(0, 0)` to `(size, size)` that no sensor rules out.
+pub fn gaps(input: &str, size: i64) -> Vec<(i64, i64)> {
     let (_, map) = file(input).unwrap();
 
-    let beacons_freqs = map
-        .iter()
-        .copied()
-        .collect::<HashSet<_>>()
+    let covered = map
         .iter()
-        .map(|Pair { beacon, .. }| beacon.x * size + beacon.y)
-        .collect::<Vec<_>>();
-
-    for row in 0..=size {
-        let mut intervals = Vec::<(i64, i64)>::new();
-
-        for Pair { sensor, beacon } in map.clone() {
-            let distance_to_row = (sensor.y - row).abs();
-
-            let distance_to_beacon = sensor.abs(beacon);
-
-            if distance_to_row > distance_to_beacon {
-                continue;
-            };
-
-            let dy = distance_to_beacon - distance_to_row;
-
-            let interval = (
-                (sensor.x - dy).max(0).min(size),
-                (sensor.x + dy).max(0).min(size),
-            );
-
-            intervals.push(interval)
-        }
-
-        intervals.sort();
-
-        let mut intervals = intervals.iter().copied().collect::<VecDeque<_>>();
-
-        let mut stack = vec![intervals.pop_front().unwrap()];
-
-        for interval in intervals {
-            let mut top = stack.pop().unwrap();
+        .map(|Pair { sensor, beacon }| {
+            Region::from(Rect::ball((sensor.x, sensor.y), sensor.abs(*beacon)))
+        })
+        .fold(Region::default(), |acc, ball| acc.union(&ball));
+
+    Region::from(Rect::around((0, 0), (size, size)))
+        .difference(&covered)
+        .cells((0, 0), (size, size))
+}
 
-            if (top.1 + 1) < interval.0 {
-                let freq = (top.1 + 1) * 4_000_000 + row;
-                if !beacons_freqs.contains(&freq) {
-                    return freq.to_string();
-                }
-                stack.push(interval);
-            } else {
-                top.1 = top.1.max(interval.1);
-                stack.push(top);
-            }
-        }
+pub fn solve_part2(input: &str, size: i64) -> String {
+    // beacons are always covered by their own sensor, so any gap is the distress beacon
+    match gaps(input, size).first() {
+        Some((x, y)) => (x * 4_000_000 + y).to_string(),
+        None => "".to_string(),
     }
-
-    "".to_string()
 }
 
 #[cfg(test)]
@@ -182,4 +125,11 @@ mod tests {
         let result = solve_part2(INPUT, 20);
         assert_eq!(result, "56000011");
     }
+
+    #[test]
+    fn gaps_works() {
+        assert_eq!(gaps(INPUT, 20), vec![(14, 11)]);
+        // outside the search area the sample leaves plenty of room
+        assert!(gaps(INPUT, 30).len() > 1);
+    }
 }
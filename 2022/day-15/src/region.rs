@@ -0,0 +1,195 @@
+//! Sets of cells built from Manhattan balls.
+//!
+//! In rotated coordinates, `u = x + y` and `v = x - y`, a ball is an axis aligned square, so
+//! unions and differences only ever cut rectangles. Only the `(u, v)` with the same parity are
+//! cells of the original grid.
+
+/// Rectangle in rotated coordinates, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub u: (i64, i64),
+    pub v: (i64, i64),
+}
+
+impl Rect {
+    /// Cells within `radius` steps of `center`.
+    pub fn ball(center: (i64, i64), radius: i64) -> Self {
+        let (u, v) = (center.0 + center.1, center.0 - center.1);
+
+        Rect {
+            u: (u - radius, u + radius),
+            v: (v - radius, v + radius),
+        }
+    }
+
+    /// Smallest rectangle holding every cell from `min` to `max`, both included.
+    pub fn around(min: (i64, i64), max: (i64, i64)) -> Self {
+        Rect {
+            u: (min.0 + min.1, max.0 + max.1),
+            v: (min.0 - max.1, max.0 - min.1),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.u.0 > self.u.1 || self.v.0 > self.v.1
+    }
+
+    fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect {
+            u: (self.u.0.max(other.u.0), self.u.1.min(other.u.1)),
+            v: (self.v.0.max(other.v.0), self.v.1.min(other.v.1)),
+        };
+
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// At most four disjoint rectangles covering `self` but not `other`.
+    fn minus(&self, other: &Rect) -> Vec<Rect> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        [
+            Rect {
+                u: (self.u.0, cut.u.0 - 1),
+                v: self.v,
+            },
+            Rect {
+                u: (cut.u.1 + 1, self.u.1),
+                v: self.v,
+            },
+            Rect {
+                u: cut.u,
+                v: (self.v.0, cut.v.0 - 1),
+            },
+            Rect {
+                u: cut.u,
+                v: (cut.v.1 + 1, self.v.1),
+            },
+        ]
+        .into_iter()
+        .filter(|rect| !rect.is_empty())
+        .collect()
+    }
+
+    /// Cells from `min` to `max` that are in the rectangle, going along `u`.
+    fn cells(&self, min: (i64, i64), max: (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        let Rect { u, v } = *self;
+
+        // every pair of bounds on v, once u is fixed, has to leave some room
+        let first =
+            u.0.max(v.0 + 2 * min.1)
+                .max(2 * min.0 - v.1)
+                .max(min.0 + min.1);
+        let last =
+            u.1.min(2 * max.0 - v.0)
+                .min(v.1 + 2 * max.1)
+                .min(max.0 + max.1);
+
+        (first..=last).flat_map(move |u| {
+            let lo = v.0.max(2 * min.0 - u).max(u - 2 * max.1);
+            let hi = v.1.min(2 * max.0 - u).min(u - 2 * min.1);
+
+            // v has the parity of u
+            let lo = lo + (lo - u).rem_euclid(2);
+
+            (lo..=hi)
+                .step_by(2)
+                .map(move |v| ((u + v) / 2, (u - v) / 2))
+        })
+    }
+}
+
+/// Union of disjoint rectangles in rotated coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Region {
+    rects: Vec<Rect>,
+}
+
+impl From<Rect> for Region {
+    fn from(rect: Rect) -> Self {
+        let rects = if rect.is_empty() { vec![] } else { vec![rect] };
+        Region { rects }
+    }
+}
+
+impl Region {
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    pub fn difference(&self, other: &Region) -> Region {
+        let rects = other.rects.iter().fold(self.rects.clone(), |rects, cut| {
+            rects.iter().flat_map(|rect| rect.minus(cut)).collect()
+        });
+
+        Region { rects }
+    }
+
+    pub fn union(&self, other: &Region) -> Region {
+        let mut rects = self.rects.clone();
+        rects.extend(other.difference(self).rects);
+
+        Region { rects }
+    }
+
+    /// Every cell of the region from `min` to `max`, both included, sorted.
+    pub fn cells(&self, min: (i64, i64), max: (i64, i64)) -> Vec<(i64, i64)> {
+        let mut cells = self
+            .rects
+            .iter()
+            .flat_map(|rect| rect.cells(min, max))
+            .collect::<Vec<_>>();
+
+        cells.sort();
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: (i64, i64), b: (i64, i64)) -> i64 {
+        (a.0 - b.0).abs() + (a.1 - b.1).abs()
+    }
+
+    #[test]
+    fn ball_test() {
+        let ball = Region::from(Rect::ball((3, 4), 2));
+
+        let cells = ball.cells((-10, -10), (10, 10));
+
+        assert_eq!(cells.len(), 13);
+        assert!(cells.iter().all(|&c| distance(c, (3, 4)) <= 2));
+
+        // clipped by the area
+        assert_eq!(ball.cells((3, 0), (10, 4)).len(), 6);
+    }
+
+    #[test]
+    fn algebra_test() {
+        let balls = [((0, 0), 3), ((4, 1), 2), ((-2, 5), 4), ((7, 7), 1)];
+
+        let covered = balls
+            .iter()
+            .map(|&(center, radius)| Region::from(Rect::ball(center, radius)))
+            .fold(Region::default(), |acc, ball| acc.union(&ball));
+
+        let (min, max) = ((-3, -2), (8, 9));
+        let gaps = Region::from(Rect::around(min, max)).difference(&covered);
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                let inside = balls.iter().any(|&(c, r)| distance((x, y), c) <= r);
+
+                assert_eq!(covered.cells(min, max).contains(&(x, y)), inside);
+                assert_eq!(gaps.cells(min, max).contains(&(x, y)), !inside);
+            }
+        }
+
+        // disjoint rectangles never count a cell twice
+        let all = covered.cells(min, max);
+        assert!(all.windows(2).all(|w| w[0] != w[1]));
+    }
+}
@@ -0,0 +1,14 @@
+use advent_of_code_2022::plan;
+use std::{env, fs};
+
+// usage: plan [agents] [minutes]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let agents = args.get(1).map(|a| a.parse().unwrap()).unwrap_or(1);
+    let minutes = args.get(2).map(|m| m.parse().unwrap()).unwrap_or(30);
+
+    print!("{}", plan(&file, agents, minutes));
+}
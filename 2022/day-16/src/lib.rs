@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::fmt::Display;
 
 use nom::character::complete::alpha1;
 use nom::sequence::preceded;
@@ -14,34 +15,55 @@ use petgraph::algo::dijkstra;
 
 use petgraph::Graph;
 
-use petgraph::graph::NodeIndex;
-
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct Node {
     name: String,
     rate: i32,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct State<'a> {
-    time_left: i32,
-    flow: i32,
-    path: Vec<&'a NodeIndex>,
-    options: Vec<&'a NodeIndex>,
+/// A valve opened by an agent, `minute` is the number of minutes elapsed when it opens.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Open {
+    pub valve: String,
+    pub minute: i32,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct SingleState<'a> {
-    time_left: i32,
-    flow: i32,
-    path: Vec<&'a NodeIndex>,
+/// Total pressure released and the order in which each agent opens its valves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Plan {
+    pub pressure: i32,
+    pub schedules: Vec<Vec<Open>>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct PairState<'a> {
-    me: SingleState<'a>,
-    elephant: SingleState<'a>,
-    options: Vec<&'a NodeIndex>,
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "pressure released: {}", self.pressure)?;
+
+        for (agent, schedule) in self.schedules.iter().enumerate() {
+            write!(f, "agent {}:", agent + 1)?;
+
+            for open in schedule {
+                write!(f, " {}@{}", open.valve, open.minute)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The tunnels compressed to the valves with a positive flow rate, `start` is the last index.
+struct Network {
+    names: Vec<String>,
+    rates: Vec<i32>,
+    distances: Vec<Vec<i32>>,
+}
+
+impl Network {
+    fn start(&self) -> usize {
+        self.rates.len()
+    }
 }
 
 fn line(s: &str) -> IResult<&str, (Node, Vec<String>)> {
@@ -73,7 +95,7 @@ fn file(s: &str) -> IResult<&str, Vec<(Node, Vec<String>)>> {
     separated_list0(newline, line)(s)
 }
 
-pub fn solve_part1(input: &str) -> String {
+fn network(input: &str) -> Network {
     let (_, raw) = file(input).unwrap();
 
     let edges = raw
@@ -113,281 +135,211 @@ pub fn solve_part1(input: &str) -> String {
         }
     }
 
-    let mut distances = HashMap::new();
+    let (mut names, rates): (Vec<_>, Vec<_>) = valves.into_iter().unzip();
 
-    for a in node_index.values() {
-        let dist_map = dijkstra(&g, *a, None, |_| 1);
+    names.push("AA".to_string());
 
-        for (b, dist) in dist_map {
-            distances.insert((*a, b), dist);
-        }
-    }
-
-    let indexes = valves
-        .iter()
-        .map(|(name, _)| node_index.get(name).unwrap())
-        .rev()
-        .collect::<Vec<_>>();
-
-    let rates = valves
+    let distances = names
         .iter()
-        .map(|(name, rate)| (node_index.get(name).unwrap(), rate))
-        .collect::<HashMap<_, _>>();
-
-    let start = node_index.get("AA").unwrap();
-
-    let initial = State {
-        time_left: 30,
-        flow: 0,
-        path: vec![start],
-        options: indexes,
-    };
-
-    let mut discovered: HashSet<State> = HashSet::new();
-
-    let mut stack = Vec::new();
-    stack.push(initial);
+        .map(|a| {
+            let dist_map = dijkstra(&g, *node_index.get(a).unwrap(), None, |_| 1);
+
+            names
+                .iter()
+                .map(|b| {
+                    dist_map
+                        .get(node_index.get(b).unwrap())
+                        .copied()
+                        .unwrap_or(i32::MAX / 2)
+                })
+                .collect()
+        })
+        .collect();
 
-    let mut max_flow = 0;
-    let mut count = 0;
+    names.pop();
 
-    while let Some(state) = stack.pop() {
-        if !discovered.contains(&state) {
-            for next in &state.options {
-                let node = *state.path.last().unwrap();
+    Network {
+        names,
+        rates,
+        distances,
+    }
+}
 
-                let time_left =
-                    (state.time_left - distances.get(&(*node, **next)).unwrap() - 1).max(0);
+/// Where an agent stands: the valve it just opened, the minutes left and the valves opened.
+type State = (usize, i32, u32);
 
-                let rate = *rates.get(next).unwrap();
-                let flow = state.flow + rate * time_left;
+/// Best pressure and schedule for a single agent, for every set of valves it could open.
+///
+/// Each state only keeps the best pressure that reaches it and the state it came from, and
+/// states are expanded with the most minutes left first, as every move takes some.
+fn best_per_opened(network: &Network, minutes: i32) -> HashMap<u32, (i32, Vec<Open>)> {
+    let start = (network.start(), minutes, 0);
 
-                let mut path = state.path.clone();
+    let mut states = HashMap::<State, (i32, Option<State>)>::from([(start, (0, None))]);
+    let mut by_time = vec![vec![]; minutes.max(0) as usize + 1];
+    by_time[minutes.max(0) as usize].push(start);
 
-                path.push(next);
+    for time_left in (0..by_time.len()).rev() {
+        for state in std::mem::take(&mut by_time[time_left]) {
+            let (node, time_left, opened) = state;
+            let pressure = states[&state].0;
 
-                let mut options = state.options.clone();
+            for next in 0..network.rates.len() {
+                if opened & (1 << next) != 0 {
+                    continue;
+                }
 
-                options.retain(|option| option != next);
+                let time_left = time_left - network.distances[node][next] - 1;
 
-                options.sort_unstable_by(|a, b| {
-                    let a_rate = *rates.get(a).unwrap();
-                    let b_rate = *rates.get(b).unwrap();
+                if time_left <= 0 {
+                    continue;
+                }
 
-                    b_rate.cmp(a_rate)
-                });
+                let to = (next, time_left, opened | (1 << next));
+                let pressure = pressure + network.rates[next] * time_left;
 
-                if options.is_empty() || time_left == 0 {
-                    count += 1;
-                    if max_flow < flow {
-                        max_flow = flow;
-                        println!("new max {} after {}", &max_flow, &count);
+                match states.get_mut(&to) {
+                    Some(best) if best.0 >= pressure => {}
+                    Some(best) => *best = (pressure, Some(state)),
+                    None => {
+                        states.insert(to, (pressure, Some(state)));
+                        by_time[time_left as usize].push(to);
                     }
-                } else {
-                    stack.push(State {
-                        time_left,
-                        flow,
-                        path,
-                        options,
-                    });
                 }
             }
-        };
-        discovered.insert(state);
+        }
     }
 
-    max_flow.to_string()
-}
+    let mut best = HashMap::<u32, (i32, State)>::new();
 
-pub fn solve_part2(input: &str) -> String {
-    let (_, raw) = file(input).unwrap();
+    for (&state, &(pressure, _)) in &states {
+        let entry = best.entry(state.2).or_insert((pressure, state));
 
-    let edges = raw
-        .iter()
-        .map(|(origin, destinations)| (origin.name.clone(), destinations.clone()))
-        .collect::<HashMap<_, _>>();
+        if (entry.0, entry.1) < (pressure, state) {
+            *entry = (pressure, state);
+        }
+    }
 
-    let valves = raw
-        .iter()
-        .filter_map(|(node, _)| {
-            if node.rate > 0 {
-                Some((node.name.clone(), node.rate))
-            } else {
-                None
+    best.into_iter()
+        .map(|(opened, (pressure, mut state))| {
+            let mut path = vec![];
+
+            while let Some(from) = states[&state].1 {
+                path.push(Open {
+                    valve: network.names[state.0].clone(),
+                    minute: minutes - state.1,
+                });
+                state = from;
             }
-        })
-        .collect::<Vec<_>>();
 
-    let mut g = Graph::<Node, ()>::new();
+            path.reverse();
 
-    let node_index = raw
-        .into_iter()
-        .map(|(node, _)| {
-            let name = node.name.clone();
-            let node_index = g.add_node(node);
-            (name, node_index)
+            (opened, (pressure, path))
         })
-        .collect::<HashMap<_, _>>();
-
-    for (name, index) in &node_index {
-        let dests = edges.get(name).unwrap();
-
-        for dest in dests {
-            let index_2 = node_index.get(dest).unwrap();
-
-            g.add_edge(*index, *index_2, ());
-        }
-    }
-
-    let mut distances = HashMap::new();
+        .collect()
+}
 
-    for a in node_index.values() {
-        let dist_map = dijkstra(&g, *a, None, |_| 1);
+/// Finds the most pressure `agents` agents can release in `minutes`.
+///
+/// Each agent works alone on a disjoint set of valves, so the best single agent
+/// plan for every opened set is combined over splits of all the valves.
+pub fn plan(input: &str, agents: usize, minutes: i32) -> Plan {
+    assert!(agents > 0, "at least one agent is needed");
 
-        for (b, dist) in dist_map {
-            distances.insert((*a, b), dist);
-        }
-    }
+    let network = network(input);
 
-    let mut indexes = valves
-        .iter()
-        .map(|(name, _)| node_index.get(name).unwrap())
-        .rev()
-        .collect::<Vec<_>>();
+    let valves = network.rates.len();
 
-    let rates = valves
-        .iter()
-        .map(|(name, rate)| (node_index.get(name).unwrap(), rate))
-        .collect::<HashMap<_, _>>();
+    assert!(valves < 32, "too many valves with positive flow rate");
 
-    indexes.sort_unstable_by(|a, b| {
-        let a_rate = *rates.get(a).unwrap();
-        let b_rate = *rates.get(b).unwrap();
+    let full = (1u32 << valves) - 1;
 
-        b_rate.cmp(a_rate)
-    });
+    let best = best_per_opened(&network, minutes);
 
-    let start = node_index.get("AA").unwrap();
+    // best single agent pressure using only valves from the mask, and which mask achieves it
+    let mut single = vec![(0, 0u32); 1 << valves];
 
-    let initial = SingleState {
-        time_left: 26,
-        flow: 0,
-        path: vec![start],
-    };
+    for (opened, (pressure, _)) in &best {
+        single[*opened as usize] = (*pressure, *opened);
+    }
 
-    let initial = PairState {
-        me: initial.clone(),
-        elephant: initial.clone(),
-        options: indexes,
-    };
+    for bit in 0..valves {
+        for mask in 0..=full {
+            if mask & (1 << bit) != 0 {
+                let without = single[(mask ^ (1 << bit)) as usize];
 
-    let mut discovered: HashSet<PairState> = HashSet::new();
+                if single[mask as usize].0 < without.0 {
+                    single[mask as usize] = without;
+                }
+            }
+        }
+    }
 
-    let mut stack = Vec::new();
-    stack.push(initial);
+    // layers[j][mask] is the best pressure for j + 1 agents using only valves from the mask,
+    // and the valves the last agent opens
+    let mut layers = vec![single.clone()];
 
-    let mut max_flow = 0;
-    let mut count = 0;
+    for agent in 1..agents {
+        let previous = &layers[agent - 1];
 
-    while let Some(state) = stack.pop() {
-        if !discovered.contains(&state) {
-            for next in &state.options {
-                let rate = *rates.get(next).unwrap();
+        let masks = if agent + 1 == agents {
+            vec![full]
+        } else {
+            (0..=full).collect()
+        };
 
-                let me_node = *state.me.path.last().unwrap();
+        let mut layer = vec![(0, 0u32); 1 << valves];
 
-                let me_time_left =
-                    (state.me.time_left - distances.get(&(*me_node, **next)).unwrap() - 1).max(0);
+        for mask in masks {
+            let mut sub = mask;
 
-                let me_flow = state.me.flow + rate * me_time_left;
-                let mut me_path = state.me.path.clone();
-                me_path.push(next);
+            loop {
+                let (pressure, opened) = single[sub as usize];
+                let pressure = pressure + previous[(mask ^ opened) as usize].0;
 
-                let elephant_node = *state.elephant.path.last().unwrap();
+                if layer[mask as usize].0 <= pressure {
+                    layer[mask as usize] = (pressure, opened);
+                }
 
-                let elephant_time_left = (state.elephant.time_left
-                    - distances.get(&(*elephant_node, **next)).unwrap()
-                    - 1)
-                .max(0);
+                if sub == 0 {
+                    break;
+                }
 
-                let elephant_flow = state.elephant.flow + rate * elephant_time_left;
-                let mut elephant_path = state.elephant.path.clone();
-                elephant_path.push(next);
+                sub = (sub - 1) & mask;
+            }
+        }
 
-                let mut options = state.options.clone();
+        layers.push(layer);
+    }
 
-                options.retain(|option| {
-                    if option != next {
-                        return true;
-                    }
+    let mut schedules = vec![];
 
-                    let d = *distances.get(&(**next, **option)).unwrap();
+    let mut mask = full;
 
-                    d >= (me_time_left.max(elephant_time_left) + 1)
-                });
+    for layer in layers.iter().rev() {
+        let (_, opened) = layer[mask as usize];
 
-                if options.is_empty() || (me_time_left == 0 && elephant_time_left == 0) {
-                    count += 1;
+        schedules.push(
+            best.get(&opened)
+                .map(|(_, path)| path.clone())
+                .unwrap_or_default(),
+        );
 
-                    let both_flow =
-                        (me_flow + state.elephant.flow).max(elephant_flow + state.me.flow);
+        mask ^= opened;
+    }
 
-                    if max_flow < both_flow {
-                        max_flow = both_flow;
-                        println!("new max {} after {}", &max_flow, &count);
-                    }
-                } else {
-                    let me_state = SingleState {
-                        time_left: me_time_left,
-                        flow: me_flow,
-                        path: me_path,
-                    };
-
-                    let elephant_state = SingleState {
-                        time_left: elephant_time_left,
-                        flow: elephant_flow,
-                        path: elephant_path,
-                    };
-
-                    if elephant_time_left > me_time_left {
-                        if elephant_time_left > 0 {
-                            stack.push(PairState {
-                                me: state.me.clone(),
-                                elephant: elephant_state,
-                                options: options.clone(),
-                            });
-                        }
-
-                        if me_time_left > 0 {
-                            stack.push(PairState {
-                                me: me_state,
-                                elephant: state.elephant.clone(),
-                                options: options.clone(),
-                            });
-                        }
-                    } else {
-                        if me_time_left > 0 {
-                            stack.push(PairState {
-                                me: me_state,
-                                elephant: state.elephant.clone(),
-                                options: options.clone(),
-                            });
-                        }
-                        if elephant_time_left > 0 {
-                            stack.push(PairState {
-                                me: state.me.clone(),
-                                elephant: elephant_state,
-                                options: options.clone(),
-                            });
-                        }
-                    }
-                }
-            }
-        };
-        discovered.insert(state);
+    Plan {
+        pressure: layers.last().unwrap()[full as usize].0,
+        schedules,
     }
+}
 
-    max_flow.to_string()
+pub fn solve_part1(input: &str) -> String {
+    plan(input, 1, 30).pressure.to_string()
+}
+
+pub fn solve_part2(input: &str) -> String {
+    plan(input, 2, 26).pressure.to_string()
 }
 
 #[cfg(test)]
@@ -407,4 +359,119 @@ mod tests {
         let result = solve_part2(INPUT);
         assert_eq!(result, "1707");
     }
+
+    fn opens(schedule: &[Open]) -> Vec<(&str, i32)> {
+        schedule
+            .iter()
+            .map(|open| (open.valve.as_str(), open.minute))
+            .collect()
+    }
+
+    #[test]
+    fn single_schedule_works() {
+        let result = plan(INPUT, 1, 30);
+
+        assert_eq!(result.pressure, 1651);
+        assert_eq!(
+            opens(&result.schedules[0]),
+            vec![
+                ("DD", 2),
+                ("BB", 5),
+                ("JJ", 9),
+                ("HH", 17),
+                ("EE", 21),
+                ("CC", 24)
+            ]
+        );
+    }
+
+    #[test]
+    fn pair_schedule_works() {
+        let result = plan(INPUT, 2, 26);
+
+        let mut schedules = result
+            .schedules
+            .iter()
+            .map(|s| opens(s))
+            .collect::<Vec<_>>();
+        schedules.sort();
+
+        assert_eq!(
+            schedules,
+            vec![
+                vec![("DD", 2), ("HH", 7), ("EE", 11)],
+                vec![("JJ", 3), ("BB", 7), ("CC", 9)],
+            ]
+        );
+    }
+
+    /// Most pressure one agent releases opening every valve of `mask`, trying each order.
+    fn brute_force_single(
+        network: &Network,
+        node: usize,
+        time_left: i32,
+        mask: u32,
+    ) -> Option<i32> {
+        if mask == 0 {
+            return Some(0);
+        }
+
+        (0..network.rates.len())
+            .filter(|&next| mask & (1 << next) != 0)
+            .filter_map(|next| {
+                let time_left = time_left - network.distances[node][next] - 1;
+
+                if time_left <= 0 {
+                    return None;
+                }
+
+                let rest = brute_force_single(network, next, time_left, mask ^ (1 << next))?;
+
+                Some(rest + network.rates[next] * time_left)
+            })
+            .max()
+    }
+
+    /// Most pressure released when each valve is given to one of the agents or to none.
+    fn brute_force(agents: usize, minutes: i32) -> i32 {
+        let network = network(INPUT);
+        let valves = network.rates.len();
+
+        let single = (0..1 << valves)
+            .map(|mask| brute_force_single(&network, network.start(), minutes, mask))
+            .collect::<Vec<_>>();
+
+        (0..(agents + 1).pow(valves as u32))
+            .filter_map(|mut split| {
+                let mut masks = vec![0; agents];
+
+                for valve in 0..valves {
+                    if split % (agents + 1) > 0 {
+                        masks[split % (agents + 1) - 1] |= 1 << valve;
+                    }
+
+                    split /= agents + 1;
+                }
+
+                masks.iter().map(|&mask| single[mask]).sum::<Option<i32>>()
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn many_agents_works() {
+        for (agents, minutes) in [(1, 30), (2, 26), (3, 26), (3, 10), (4, 12), (2, 0)] {
+            let result = plan(INPUT, agents, minutes);
+            assert_eq!(result.pressure, brute_force(agents, minutes));
+        }
+
+        let result = plan(INPUT, 3, 26);
+
+        let all = result.schedules.iter().flatten().count();
+
+        assert_eq!(result.schedules.len(), 3);
+        assert_eq!(all, 6);
+        assert_eq!(result.pressure, 1794);
+    }
 }
@@ -0,0 +1,32 @@
+use advent_of_code_2022::{frames, render, ROCKS, WIDTH};
+use aoc_render::{save_png, Animation, Palette, BLUE, GRAY, RED, WHITE};
+use std::{env, fs};
+
+// usage: animate [num pieces] [rows] [keep one frame every]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let num_pieces = args.get(1).map(|n| n.parse().unwrap()).unwrap_or(50);
+    let rows = args.get(2).map(|n| n.parse().unwrap()).unwrap_or(40);
+    let every = args.get(3).map(|n| n.parse().unwrap()).unwrap_or(1);
+
+    let palette = Palette::new(WHITE)
+        .tile('#', GRAY)
+        .tile('@', RED)
+        .tile('~', BLUE);
+
+    let mut animation = Animation::new(palette.clone(), 8).delay(5).every(every);
+
+    for frame in frames(&file, ROCKS, WIDTH, num_pieces, rows).unwrap() {
+        animation.push(&frame);
+    }
+
+    animation.save_gif("chamber.gif").unwrap();
+
+    let chamber = render(&file, ROCKS, WIDTH, num_pieces, 0..=rows).unwrap();
+    save_png("chamber.png", &chamber, &palette, 8).unwrap();
+
+    println!("{} frames saved to chamber.gif", animation.len());
+}
@@ -0,0 +1,31 @@
+use advent_of_code_2022::{render, tower_height, ROCKS, WIDTH};
+use std::{env, fs};
+
+// usage: custom [rocks file] [width] [num pieces] [rows to render, from-to]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let rocks = args
+        .get(1)
+        .map(|path| fs::read_to_string(path).unwrap())
+        .unwrap_or(ROCKS.to_string());
+
+    let width = args.get(2).map(|w| w.parse().unwrap()).unwrap_or(WIDTH);
+
+    let num_pieces = args.get(3).map(|n| n.parse().unwrap()).unwrap_or(2022);
+
+    if let Some((from, to)) = args.get(4).and_then(|rows| rows.split_once('-')) {
+        let rows = from.parse().unwrap()..=to.parse().unwrap();
+        println!(
+            "{}",
+            render(&file, &rocks, width, num_pieces, rows).unwrap()
+        );
+    }
+
+    println!(
+        "{}",
+        tower_height(&file, &rocks, width, num_pieces).unwrap()
+    );
+}
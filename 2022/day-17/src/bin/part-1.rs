@@ -3,5 +3,5 @@ use std::fs;
 
 fn main() {
     let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", solve_part1(&file, 2022));
+    println!("{}", solve_part1(&file, 2022).unwrap());
 }
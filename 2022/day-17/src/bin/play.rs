@@ -0,0 +1,18 @@
+use advent_of_code_2022::{frames, ROCKS, WIDTH};
+use aoc_render::terminal::Player;
+use std::{env, fs};
+
+// usage: play [num pieces] [frames per second]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let num_pieces = args.get(1).map(|n| n.parse().unwrap()).unwrap_or(2022);
+    let fps = args.get(2).map(|n| n.parse().unwrap()).unwrap_or(30.0);
+
+    // a chamber taller than any terminal, the view follows the falling rock
+    Player::new(fps)
+        .play(frames(&file, ROCKS, WIDTH, num_pieces, 200).unwrap())
+        .unwrap();
+}
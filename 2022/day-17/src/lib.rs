@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
-    fmt::Debug,
-    ops::Add,
+    fmt::{Debug, Display},
+    ops::{Add, RangeInclusive},
 };
 
 #[derive(Clone, Copy)]
@@ -11,27 +11,27 @@ enum Push {
 }
 
 #[derive(Clone)]
-enum Shape {
-    A,
-    B,
-    C,
-    D,
-    E,
+struct Shape {
+    body: Vec<Coords>,
 }
 
-use Shape::*;
+impl Shape {
+    fn width(&self) -> i32 {
+        self.body.iter().map(|p| p.x).max().unwrap_or(-1) + 1
+    }
+}
 
 struct Factory {
-    shapes: [Shape; 5],
+    shapes: Vec<Shape>,
     shape_index: i32,
     pushes: Vec<Push>,
     push_index: i32,
 }
 
 impl Factory {
-    fn new(pushes: Vec<Push>) -> Self {
+    fn new(shapes: Vec<Shape>, pushes: Vec<Push>) -> Self {
         Self {
-            shapes: [Shape::A, Shape::B, Shape::C, Shape::D, Shape::E],
+            shapes,
             shape_index: -1,
             pushes,
             push_index: -1,
@@ -73,50 +73,7 @@ struct Piece {
 
 impl Piece {
     pub fn new(shape: Shape, position: Coords) -> Self {
-        let body = match shape {
-            A => vec![
-                Coords { x: 0, y: 0 },
-                Coords { x: 1, y: 0 },
-                Coords { x: 2, y: 0 },
-                Coords { x: 3, y: 0 },
-            ],
-            B => vec![
-                Coords { x: 1, y: 0 },
-                Coords { x: 0, y: 1 },
-                Coords { x: 1, y: 1 },
-                Coords { x: 2, y: 1 },
-                Coords { x: 1, y: 2 },
-            ],
-            C => vec![
-                Coords { x: 0, y: 0 },
-                Coords { x: 1, y: 0 },
-                Coords { x: 2, y: 0 },
-                Coords { x: 2, y: 1 },
-                Coords { x: 2, y: 2 },
-            ],
-            D => vec![
-                Coords { x: 0, y: 0 },
-                Coords { x: 0, y: 1 },
-                Coords { x: 0, y: 2 },
-                Coords { x: 0, y: 3 },
-            ],
-            E => vec![
-                Coords { x: 0, y: 0 },
-                Coords { x: 1, y: 0 },
-                Coords { x: 0, y: 1 },
-                Coords { x: 1, y: 1 },
-            ],
-        };
-
-        let body = body
-            .iter()
-            .map(|p| {
-                *p + Coords {
-                    x: position.x,
-                    y: position.y,
-                }
-            })
-            .collect();
+        let body = shape.body.iter().map(|p| *p + position).collect();
 
         Self { body }
     }
@@ -162,25 +119,24 @@ struct Grid {
 
 impl Debug for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let floor = self.grid.floor();
+
+        let rows = self.window.clone().unwrap_or(0..=(self.grid.height() + 6));
+
         let mut output = "\n".to_string();
-        for y in (0..=(self.grid.height() + 6)).rev() {
-            for x in 0..=(self.grid.width + 1) {
-                match self.grid.solid.contains(&Coords {
-                    x: x as i32,
-                    y: y as i64,
-                }) {
-                    true => output += "#",
-                    false => {
-                        if self.piece.body.contains(&Coords {
-                            x: x as i32,
-                            y: y as i64,
-                        }) {
-                            output += "@"
-                        } else {
-                            output += "."
-                        }
-                    }
-                };
+        for y in rows.rev() {
+            for x in 0..=(self.grid.width as i32 + 1) {
+                let p = Coords { x, y };
+
+                if y < floor && 0 < x && x <= self.grid.width as i32 {
+                    output += "~";
+                } else if self.grid.solid.contains(&p) {
+                    output += "#";
+                } else if self.piece.body.contains(&p) {
+                    output += "@";
+                } else {
+                    output += ".";
+                }
             }
             output += "\n";
         }
@@ -230,23 +186,25 @@ impl Grid {
         floor
     }
 
-    pub fn columns(&self) -> Vec<Vec<i64>> {
-        let floor = self.floor();
+    /// Solid cells in the top `rows` rows, or down to the floor if it is closer, counted down
+    /// from the top of the tower.
+    pub fn top(&self, rows: i64) -> Vec<Coords> {
+        let height = self.height();
+        let bottom = self.floor().max(height - rows + 1);
 
-        (1..=self.width)
-            .map(|x| {
-                self.solid
-                    .iter()
-                    .filter_map(|p| {
-                        if p.x == x as i32 && p.y >= floor {
-                            Some(p.y - floor)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
+        let mut cells = self
+            .solid
+            .iter()
+            .filter(|p| p.y >= bottom)
+            .map(|p| Coords {
+                x: p.x,
+                y: height - p.y,
             })
-            .collect()
+            .collect::<Vec<_>>();
+
+        cells.sort_by_key(|p| (p.y, p.x));
+
+        cells
     }
 }
 
@@ -254,6 +212,7 @@ struct Board {
     grid: Grid,
     piece: Piece,
     factory: Factory,
+    window: Option<RangeInclusive<i64>>,
 }
 
 impl Board {
@@ -264,6 +223,7 @@ impl Board {
             piece,
             grid,
             factory,
+            window: None,
         }
     }
 
@@ -271,7 +231,7 @@ impl Board {
         Piece::new(shape, position)
     }
 
-    pub fn pop_and_drop(&mut self) {
+    fn spawn(&mut self) {
         let shape = self.factory.next_shape();
 
         self.piece = Self::pop(
@@ -281,22 +241,32 @@ impl Board {
             },
             shape,
         );
+    }
 
-        let mut piece_moved = true;
+    /// Pushes the falling rock with the next jet and lets it fall, consolidates it once it lands.
+    fn step(&mut self) -> bool {
+        let push = self.factory.next_push();
+        let piece_moved = self.piece.push(&push, &self.grid);
 
-        while piece_moved {
-            let push = self.factory.next_push();
-            piece_moved = self.piece.push(&push, &self.grid);
+        if !piece_moved {
+            self.grid.consolidate(&self.piece);
         }
 
-        self.grid.consolidate(&self.piece);
+        piece_moved
     }
 
-    pub fn state(&self) -> State {
+    pub fn pop_and_drop(&mut self) {
+        self.spawn();
+
+        while self.step() {}
+    }
+
+    /// Next rock, next jet and the top `rows` rows of the tower.
+    pub fn state(&self, rows: i64) -> State {
         State {
             shape_index: self.factory.shape_index,
             push_index: self.factory.push_index,
-            colums: self.grid.columns(),
+            top: self.grid.top(rows),
         }
     }
 }
@@ -311,81 +281,275 @@ fn parser(s: &str) -> Vec<Push> {
         .collect()
 }
 
-pub fn solve_part1(input: &str, num_pieces: u32) -> String {
-    let pushes = parser(input);
-    let factory = Factory::new(pushes);
+/// Parses a rock set: `#` shapes drawn top to bottom, separated by blank lines.
+fn rocks_parser(s: &str) -> Vec<Shape> {
+    s.replace("\r\n", "\n")
+        .split("\n\n")
+        .filter(|block| block.contains('#'))
+        .map(|block| {
+            let lines = block
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .collect::<Vec<_>>();
+
+            let body = lines
+                .iter()
+                .rev()
+                .enumerate()
+                .flat_map(|(y, line)| {
+                    line.chars().enumerate().filter_map(move |(x, c)| {
+                        (c == '#').then_some(Coords {
+                            x: x as i32,
+                            y: y as i64,
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
 
-    let grid = Grid::new(7);
+            let min_x = body.iter().map(|p| p.x).min().unwrap();
+            let min_y = body.iter().map(|p| p.y).min().unwrap();
 
-    let mut board = Board::new(grid, factory);
+            let body = body
+                .into_iter()
+                .map(|p| Coords {
+                    x: p.x - min_x,
+                    y: p.y - min_y,
+                })
+                .collect();
 
-    let mut count = 0;
+            Shape { body }
+        })
+        .collect()
+}
 
-    while count < num_pieces {
-        count += 1;
-        board.pop_and_drop();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChamberError {
+    NoJets,
+    NoRocks,
+    /// Rocks appear two units away from the left wall and need to fit from there.
+    TooWide {
+        rock: i32,
+        width: u32,
+    },
+    NoCycle {
+        rocks: u64,
+    },
+}
+
+impl Display for ChamberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChamberError::NoJets => write!(f, "no jet pushes in input"),
+            ChamberError::NoRocks => write!(f, "no rocks in rock set"),
+            ChamberError::TooWide { rock, width } => {
+                write!(
+                    f,
+                    "a rock {rock} wide does not fit in a chamber {width} wide"
+                )
+            }
+            ChamberError::NoCycle { rocks } => {
+                write!(f, "the tower does not repeat within {rocks} rocks")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChamberError {}
+
+fn board(input: &str, rocks: &str, width: u32) -> Result<Board, ChamberError> {
+    let pushes = parser(input);
+    let shapes = rocks_parser(rocks);
+
+    if pushes.is_empty() {
+        return Err(ChamberError::NoJets);
+    }
+
+    let Some(widest) = shapes.iter().map(|shape| shape.width()).max() else {
+        return Err(ChamberError::NoRocks);
+    };
+
+    if widest + 2 > width as i32 {
+        return Err(ChamberError::TooWide {
+            rock: widest,
+            width,
+        });
     }
 
-    board.grid.height().to_string()
+    let factory = Factory::new(shapes, pushes);
+
+    let grid = Grid::new(width);
+
+    Ok(Board::new(grid, factory))
+}
+
+/// The five rocks of the original puzzle, in falling order.
+pub const ROCKS: &str = include_str!("../rocks.txt");
+
+pub const WIDTH: u32 = 7;
+
+/// Rows at the top of the tower compared while looking for a cycle, the window repeats even if
+/// some column never fills. A tall rock or a deep shaft can reach below it, so two states that
+/// look the same are only a candidate cycle until the heights repeat too.
+const WINDOW: i64 = 64;
+
+/// Rocks dropped while looking for a cycle before giving up.
+pub const CYCLE_SEARCH: u64 = 100_000;
+
+pub fn solve_part1(input: &str, num_pieces: u32) -> Result<String, ChamberError> {
+    Ok(tower_height(input, ROCKS, WIDTH, num_pieces as u64)?.to_string())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct State {
     shape_index: i32,
     push_index: i32,
-    colums: Vec<Vec<i64>>,
+    top: Vec<Coords>,
 }
 
-struct Result {
-    num_pieces: u64,
-    height: u64,
+pub fn solve_part2(input: &str, num_pieces: u64) -> Result<String, ChamberError> {
+    Ok(tower_height(input, ROCKS, WIDTH, num_pieces)?.to_string())
 }
 
-pub fn solve_part2(input: &str, num_pieces: u64) -> String {
-    let pushes = parser(input);
-    let factory = Factory::new(pushes);
+/// Height of the tower after `num_pieces` rocks from `rocks` fall into a chamber `width` wide.
+///
+/// Drops rocks until the top [`WINDOW`] rows of the tower, the next rock and the next jet
+/// repeat, and the tower grows the same way over the following cycle. Then skips whole cycles
+/// and looks up the height of the leftover pieces. Gives up after [`CYCLE_SEARCH`] rocks.
+pub fn tower_height(
+    input: &str,
+    rocks: &str,
+    width: u32,
+    num_pieces: u64,
+) -> Result<u128, ChamberError> {
+    search_cycle(input, rocks, width, num_pieces, WINDOW, CYCLE_SEARCH)
+}
 
-    let grid = Grid::new(7);
+fn search_cycle(
+    input: &str,
+    rocks: &str,
+    width: u32,
+    num_pieces: u64,
+    window: i64,
+    limit: u64,
+) -> Result<u128, ChamberError> {
+    let mut board = board(input, rocks, width)?;
 
-    let mut board = Board::new(grid, factory);
+    let mut cache = HashMap::<State, u64>::new();
 
-    let mut cache = HashMap::<State, Result>::new();
+    let mut heights = vec![0];
 
     let mut count = 0;
 
-    let (pieces, height) = loop {
+    let mut candidate = None;
+
+    let (start, pieces) = loop {
+        if count == num_pieces {
+            return Ok(board.grid.height() as u128);
+        }
+
+        if count == limit {
+            return Err(ChamberError::NoCycle { rocks: limit });
+        }
+
         count += 1;
 
         board.pop_and_drop();
 
-        let state = board.state();
+        heights.push(board.grid.height());
 
-        let result = Result {
-            num_pieces: count,
-            height: board.grid.height() as u64,
-        };
+        if let Some((start, pieces)) = candidate {
+            if count == start + 2 * pieces {
+                let grown = |from: u64| {
+                    let from = from as usize;
+                    heights[from..=from + pieces as usize]
+                        .iter()
+                        .map(|h| h - heights[from])
+                        .collect::<Vec<_>>()
+                };
 
-        if cache.contains_key(&state) {
-            let prev = cache.get(&state).unwrap();
+                if grown(start) == grown(start + pieces) {
+                    break (start, pieces);
+                }
 
-            break (
-                count - prev.num_pieces,
-                board.grid.height() - prev.height as i64,
-            );
+                candidate = None;
+            }
         }
 
-        cache.insert(state, result);
+        let state = board.state(window);
+
+        if let Some(prev) = cache.insert(state, count) {
+            candidate.get_or_insert((prev, count - prev));
+        }
     };
 
-    let mut total_height: u128 = (num_pieces as u128 / pieces as u128) * height as u128;
+    let height = (heights[(start + pieces) as usize] - heights[start as usize]) as u128;
+
+    let cycles = (num_pieces - start) / pieces;
+    let remainding_pieces = (num_pieces - start) % pieces;
+
+    Ok(heights[(start + remainding_pieces) as usize] as u128 + cycles as u128 * height)
+}
+
+/// Drops `num_pieces` rocks and renders the `rows` of the chamber, bottom row is `1`.
+///
+/// Rows below the floor are discarded while simulating and drawn as `~`.
+pub fn render(
+    input: &str,
+    rocks: &str,
+    width: u32,
+    num_pieces: u64,
+    rows: RangeInclusive<i64>,
+) -> Result<String, ChamberError> {
+    let mut board = board(input, rocks, width)?;
+
+    for _ in 0..num_pieces {
+        board.pop_and_drop();
+    }
 
-    let remainding_pieces = num_pieces % pieces;
+    board.window = Some(rows);
 
-    total_height += solve_part1(input, remainding_pieces as u32)
-        .parse::<u128>()
-        .unwrap();
+    Ok(format!("{:?}", board))
+}
 
-    total_height.to_string()
+/// Chamber after every jet push while dropping `num_pieces` rocks, the top `rows` rows
+/// around the falling rock.
+pub fn frames<'a>(
+    input: &'a str,
+    rocks: &'a str,
+    width: u32,
+    num_pieces: u64,
+    rows: i64,
+) -> Result<impl Iterator<Item = String> + 'a, ChamberError> {
+    let mut board = board(input, rocks, width)?;
+    let mut dropped = 0;
+    let mut falling = false;
+
+    Ok(std::iter::from_fn(move || {
+        if falling {
+            falling = board.step();
+        } else if dropped < num_pieces {
+            board.spawn();
+            dropped += 1;
+            falling = true;
+        } else {
+            return None;
+        }
+
+        let top = board
+            .piece
+            .body
+            .iter()
+            .map(|p| p.y)
+            .max()
+            .unwrap_or(0)
+            .max(board.grid.height())
+            + 1;
+
+        let bottom = (top + 1 - rows).max(0);
+        board.window = Some(bottom..=bottom + rows - 1);
+
+        Some(format!("{:?}", board))
+    }))
 }
 
 #[cfg(test)]
@@ -396,13 +560,105 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = solve_part1(INPUT, 2022);
+        let result = solve_part1(INPUT, 2022).unwrap();
         assert_eq!(result, "3068");
     }
 
     #[test]
     fn part2_works() {
-        let result = solve_part2(INPUT, 1_000_000_000_000);
+        let result = solve_part2(INPUT, 1_000_000_000_000).unwrap();
         assert_eq!(result, "1514285714288");
     }
+
+    fn simulate(rocks: &str, width: u32, num_pieces: u64) -> u128 {
+        let mut board = board(INPUT, rocks, width).unwrap();
+
+        for _ in 0..num_pieces {
+            board.pop_and_drop();
+        }
+
+        board.grid.height() as u128
+    }
+
+    #[test]
+    fn custom_width_works() {
+        for num_pieces in [1_000, 2_022, 3_001] {
+            let result = tower_height(INPUT, ROCKS, 9, num_pieces).unwrap();
+            assert_eq!(result, simulate(ROCKS, 9, num_pieces));
+        }
+    }
+
+    #[test]
+    fn custom_rocks_works() {
+        const L_AND_I: &str = "###\n..#\n\n#\n#";
+
+        for num_pieces in [1_000, 2_022, 3_001] {
+            let result = tower_height(INPUT, L_AND_I, 5, num_pieces).unwrap();
+            assert_eq!(result, simulate(L_AND_I, 5, num_pieces));
+        }
+    }
+
+    #[test]
+    fn narrow_window_works() {
+        // the top few rows repeat well before the tower does, the heights weed those out
+        let mut board = board("<<<>>>>", ROCKS, WIDTH).unwrap();
+
+        for _ in 0..2022 {
+            board.pop_and_drop();
+        }
+
+        for window in [0, 4] {
+            let result = search_cycle("<<<>>>>", ROCKS, WIDTH, 2022, window, CYCLE_SEARCH);
+            assert_eq!(result, Ok(board.grid.height() as u128));
+        }
+    }
+
+    #[test]
+    fn unfilled_column_works() {
+        // the two left columns stay empty, only the top of the tower repeats
+        let result = tower_height(">", "#####", 7, 1_000_000_000_000);
+        assert_eq!(result, Ok(1_000_000_000_000));
+    }
+
+    #[test]
+    fn errors_work() {
+        assert_eq!(tower_height("", ROCKS, 7, 10), Err(ChamberError::NoJets));
+        assert_eq!(
+            tower_height(INPUT, "...", 7, 10),
+            Err(ChamberError::NoRocks)
+        );
+        assert_eq!(
+            tower_height(INPUT, ROCKS, 5, 10),
+            Err(ChamberError::TooWide { rock: 4, width: 5 })
+        );
+
+        // the sample only starts repeating after a few dozen rocks
+        assert_eq!(
+            search_cycle(INPUT, ROCKS, 7, 1_000_000, WINDOW, 10),
+            Err(ChamberError::NoCycle { rocks: 10 })
+        );
+        assert_eq!(search_cycle(INPUT, ROCKS, 7, 10, WINDOW, 10), Ok(17));
+    }
+
+    #[test]
+    fn render_works() {
+        let result = render("<", "#", 3, 2, 1..=3).unwrap();
+        assert_eq!(result, "\n.....\n.#...\n.#...\n");
+    }
+
+    #[test]
+    fn frames_works() {
+        let frames = frames(INPUT, ROCKS, WIDTH, 3, 10)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert!(frames
+            .iter()
+            .all(|frame| frame.trim().lines().count() == 10));
+        assert!(frames[0].contains('@'));
+
+        let last = frames.last().unwrap();
+        assert_eq!(last.matches('#').count(), 4 + 5 + 5);
+        assert!(!last.contains('@'));
+    }
 }
@@ -0,0 +1,13 @@
+use advent_of_code_2022::optimize;
+use std::{env, fs};
+
+// usage: schedule [minutes]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let minutes = env::args().nth(1).map(|m| m.parse().unwrap()).unwrap_or(24);
+
+    for plan in optimize(&file, minutes) {
+        println!("{}", plan);
+    }
+}
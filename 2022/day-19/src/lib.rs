@@ -1,265 +1,339 @@
-#![feature(array_zip)]
-
 use rayon::prelude::*;
-use std::collections::BinaryHeap;
-use std::{cmp::Ordering, collections::HashSet, fmt::Display};
+use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct RobotFactory {
-    id: i32,
-    costs: [[i32; 4]; 4], // [Ore [Ore, Clay, Obsidian, Geode], Clay, Obsidian, Geode]
-}
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1, multispace0, multispace1, newline},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list1},
+    sequence::{delimited, preceded, separated_pair, terminated},
+    IResult,
+};
 
+/// Robot costs of a blueprint, the last resource is the one to maximize.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct State {
-    robots: [i32; 4], // [Ore, Clay, Obsidian, Geode]
-    materials: [i32; 4],
-    time: i32,
-    // history: String,
+pub struct Blueprint {
+    pub id: i32,
+    pub resources: Vec<String>,
+    costs: Vec<Vec<i32>>, // [robot [resource]], one robot per resource
 }
 
-impl Display for State {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("State")
-            .field("Ore", &self.materials[0])
-            .field("Clay", &self.materials[1])
-            .field("Obisdian", &self.materials[2])
-            .field("Geode", &self.materials[3])
-            .field("Ore Bot ", &self.robots[0])
-            .field("Clay Bot", &self.robots[1])
-            .field("Obisdian Bot", &self.robots[2])
-            .field("Geode Bot", &self.robots[3])
-            .field("time", &self.time)
-            .finish()
-    }
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
+    robots: Vec<i32>,
+    materials: Vec<i32>,
+    time_left: i32,
+    builds: Vec<(i32, usize)>, // (minute, robot)
 }
 
 impl State {
-    pub fn initial() -> Self {
+    pub fn initial(resources: usize, minutes: i32) -> Self {
+        let mut robots = vec![0; resources];
+        robots[0] = 1;
+
         State {
-            robots: [1, 0, 0, 0],
-            materials: [0; 4],
-            time: 1,
-            //  history: "".to_string(),
+            robots,
+            materials: vec![0; resources],
+            time_left: minutes,
+            builds: vec![],
         }
     }
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (self.materials[3] + self.robots[3] * self.time)
-            .cmp(&(other.materials[3] + other.robots[3] * other.time))
-            .then_with(|| (self.materials[2]).cmp(&other.materials[2]))
+impl Blueprint {
+    fn target(&self) -> usize {
+        self.resources.len() - 1
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Robots of a kind beyond the most any robot costs of that resource can not be spent.
+    fn max_useful(&self) -> Vec<i32> {
+        (0..self.resources.len())
+            .map(|j| {
+                if j == self.target() {
+                    i32::MAX
+                } else {
+                    self.costs.iter().map(|cost| cost[j]).max().unwrap_or(0)
+                }
+            })
+            .collect()
     }
-}
 
-impl RobotFactory {
-    pub fn new(costs: [[i32; 4]; 4], id: i32) -> Self {
-        RobotFactory { costs, id }
+    /// Minutes to wait until robot `i` is affordable, `None` if it never will be.
+    fn wait(&self, state: &State, i: usize) -> Option<i32> {
+        self.costs[i]
+            .iter()
+            .zip(&state.materials)
+            .zip(&state.robots)
+            .map(|((&cost, &material), &robots)| {
+                if material >= cost {
+                    Some(0)
+                } else if robots == 0 {
+                    None
+                } else {
+                    Some((cost - material + robots - 1) / robots)
+                }
+            })
+            .try_fold(0, |wait, w| w.map(|w| wait.max(w)))
     }
 
-    pub fn next_states(&self, state: &State) -> Vec<State> {
+    /// Jumps ahead to each robot that can still be built in time, building it as soon as possible.
+    fn next_states(&self, state: &State, max_useful: &[i32], minutes: i32) -> Vec<State> {
         let mut states = vec![];
 
-        states.push(Self::collect(state));
+        for i in (0..self.resources.len()).rev() {
+            if state.robots[i] >= max_useful[i] {
+                continue;
+            }
 
-        let collected_state = Self::collect(state);
+            let Some(wait) = self.wait(state, i) else {
+                continue;
+            };
 
-        'robots: for i in (0..=3).rev() {
-            for j in 0..=3 {
-                if state.materials[j] < self.costs[i][j] {
-                    continue 'robots;
-                }
-            }
+            let time_left = state.time_left - wait - 1;
 
-            let state = self.build(&collected_state, i);
+            if time_left <= 0 {
+                continue;
+            }
 
-            states.push(state)
+            let materials = state
+                .materials
+                .iter()
+                .zip(&state.robots)
+                .zip(&self.costs[i])
+                .map(|((m, r), c)| m + r * (wait + 1) - c)
+                .collect();
+
+            let mut robots = state.robots.clone();
+            robots[i] += 1;
+
+            let mut builds = state.builds.clone();
+            builds.push((minutes - state.time_left + wait + 1, i));
+
+            states.push(State {
+                robots,
+                materials,
+                time_left,
+                builds,
+            });
         }
 
         states
     }
 
-    fn build(&self, state: &State, j: usize) -> State {
-        let mut robots = state.robots;
-        robots[j] += 1;
+    /// Collected target resource if nothing else is built.
+    fn idle(&self, state: &State) -> i32 {
+        let t = self.target();
+        state.materials[t] + state.robots[t] * state.time_left
+    }
 
-        State {
-            robots,
-            time: state.time,
-            materials: state.materials.zip(self.costs[j]).map(|(m, c)| m - c),
-            /*  history: state.history.clone()
-            + " +++ "
-            + &state.time.to_string()
-            + " build "
-            + &j.to_string()
-            + " +++ ",*/
-        }
+    /// Collected target resource if a target robot could be built every remaining minute.
+    fn upper_bound(&self, state: &State) -> i32 {
+        self.idle(state) + state.time_left * (state.time_left - 1) / 2
     }
 
-    fn collect(state: &State) -> State {
-        let mut new_state = State {
-            time: state.time + 1,
-            materials: state.materials.zip(state.robots).map(|(m, r)| m + r),
-            robots: state.robots,
-            // history: state.history.clone(),
-        };
+    /// Depth first search over which robot to build next, pruning by the upper bound.
+    pub fn optimize(&self, minutes: i32) -> Plan {
+        let max_useful = self.max_useful();
+
+        let initial = State::initial(self.resources.len(), minutes);
+
+        let mut best = (self.idle(&initial), vec![]);
 
-        /* new_state.history =
-        new_state.history.clone() + " >>> " + &new_state.clone().to_string() + " "; */
+        let mut stack = vec![initial];
 
-        new_state
+        while let Some(state) = stack.pop() {
+            let collected = self.idle(&state);
+
+            if collected > best.0 {
+                best = (collected, state.builds.clone());
+            }
+
+            for next in self.next_states(&state, &max_useful, minutes) {
+                if self.upper_bound(&next) > best.0 {
+                    stack.push(next);
+                }
+            }
+        }
+
+        let mut schedule = vec![None; minutes as usize];
+
+        for (minute, robot) in best.1 {
+            schedule[minute as usize - 1] = Some(robot);
+        }
+
+        Plan {
+            blueprint: self.clone(),
+            collected: best.0,
+            schedule,
+        }
     }
 }
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::separated_list1,
-    sequence::{delimited, separated_pair},
-    IResult,
-};
-
-// Blueprint 5: Each ore robot costs 4 ore. Each clay robot costs 3 ore. Each obsidian robot costs 4 ore and 8 clay. Each geode robot costs 3 ore and 7 obsidian.
+/// Best amount of the target resource and the robot, if any, to start building each minute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub blueprint: Blueprint,
+    pub collected: i32,
+    pub schedule: Vec<Option<usize>>,
+}
 
-fn geode(s: &str) -> IResult<&str, (i32, i32)> {
-    delimited(
-        tag("Each geode robot costs "),
-        separated_pair(complete::i32, tag(" ore and "), complete::i32),
-        tag(" obsidian."),
-    )(s)
+impl Plan {
+    pub fn quality_level(&self) -> i32 {
+        self.blueprint.id * self.collected
+    }
 }
 
-fn obsidian(s: &str) -> IResult<&str, (i32, i32)> {
-    delimited(
-        tag("Each obsidian robot costs "),
-        separated_pair(complete::i32, tag(" ore and "), complete::i32),
-        tag(" clay. "),
-    )(s)
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let resources = &self.blueprint.resources;
+
+        let mut robots = vec![0; resources.len()];
+        robots[0] = 1;
+
+        let mut materials = vec![0; resources.len()];
+
+        writeln!(f, "== Blueprint {} ==", self.blueprint.id)?;
+
+        for (minute, build) in self.schedule.iter().enumerate() {
+            write!(f, "minute {:>2}:", minute + 1)?;
+
+            if let Some(robot) = build {
+                for (m, c) in materials.iter_mut().zip(&self.blueprint.costs[*robot]) {
+                    *m -= c;
+                }
+
+                write!(f, " build {} robot,", resources[*robot])?;
+            }
+
+            for (m, r) in materials.iter_mut().zip(&robots) {
+                *m += r;
+            }
+
+            if let Some(robot) = build {
+                robots[*robot] += 1;
+            }
+
+            let stock = resources
+                .iter()
+                .zip(materials.iter().zip(&robots))
+                .map(|(name, (m, r))| format!("{} {} ({} robots)", m, name, r))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(f, " {}", stock)?;
+        }
+
+        writeln!(
+            f,
+            "{} {} collected",
+            self.collected,
+            resources.last().unwrap()
+        )
+    }
 }
 
-fn clay(s: &str) -> IResult<&str, i32> {
-    delimited(tag("Each clay robot costs "), complete::i32, tag(" ore. "))(s)
+// Blueprint 5: Each ore robot costs 4 ore. Each clay robot costs 3 ore. Each obsidian robot costs 4 ore and 8 clay. Each geode robot costs 3 ore and 7 obsidian.
+
+type Costs<'a> = Vec<(i32, &'a str)>;
+
+fn cost(s: &str) -> IResult<&str, (i32, &str)> {
+    separated_pair(complete::i32, tag(" "), alpha1)(s)
 }
 
-fn ore(s: &str) -> IResult<&str, i32> {
-    delimited(tag("Each ore robot costs "), complete::i32, tag(" ore. "))(s)
+fn robot(s: &str) -> IResult<&str, (&str, Costs<'_>)> {
+    delimited(
+        preceded(multispace0, tag("Each ")),
+        separated_pair(
+            alpha1,
+            tag(" robot costs "),
+            separated_list1(tag(" and "), cost),
+        ),
+        tag("."),
+    )(s)
 }
 
 fn id(s: &str) -> IResult<&str, i32> {
-    delimited(tag("Blueprint "), complete::i32, tag(": "))(s)
+    delimited(
+        tag("Blueprint "),
+        complete::i32,
+        terminated(tag(":"), multispace1),
+    )(s)
 }
 
-fn blueprint(s: &str) -> IResult<&str, RobotFactory> {
+fn blueprint(s: &str) -> IResult<&str, Blueprint> {
     let (s, id) = id(s)?;
 
-    let (s, ore) = ore(s)?;
-    let ore_costs = [ore, 0, 0, 0];
+    let (s, robots) = many1(robot)(s)?;
 
-    let (s, ore) = clay(s)?;
-    let clay_costs = [ore, 0, 0, 0];
+    let resources = robots
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
 
-    let (s, (ore, clay)) = obsidian(s)?;
-    let obsidian_costs = [ore, clay, 0, 0];
+    // a cost in something no robot collects could never be paid
+    if let Some((_, unknown)) = robots
+        .iter()
+        .flat_map(|(_, costs)| costs)
+        .find(|(_, name)| !resources.iter().any(|resource| resource == name))
+    {
+        return Err(nom::Err::Failure(Error::new(unknown, ErrorKind::Verify)));
+    }
 
-    let (s, (ore, obsidian)) = geode(s)?;
-    let geode_costs = [ore, 0, obsidian, 0];
+    let costs = robots
+        .iter()
+        .map(|(_, costs)| {
+            resources
+                .iter()
+                .map(|resource| {
+                    costs
+                        .iter()
+                        .filter(|(_, name)| name == resource)
+                        .map(|(amount, _)| amount)
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
 
     Ok((
         s,
-        RobotFactory::new([ore_costs, clay_costs, obsidian_costs, geode_costs], id),
+        Blueprint {
+            id,
+            resources,
+            costs,
+        },
     ))
 }
 
-fn parse(s: &str) -> IResult<&str, Vec<RobotFactory>> {
+fn parse(s: &str) -> IResult<&str, Vec<Blueprint>> {
     separated_list1(newline, blueprint)(s)
 }
 
-pub fn solve_part1(input: &str) -> String {
-    let (_, factories) = parse(input).unwrap();
+/// Optimal plan for every blueprint in the input, evaluated in parallel.
+pub fn optimize(input: &str, minutes: i32) -> Vec<Plan> {
+    let (_, blueprints) = parse(input).unwrap();
 
-    let mut sum_quality_levels = 0;
-
-    for factory in factories {
-        let mut visited = HashSet::new();
-
-        let mut stack = BinaryHeap::from(vec![State::initial()]);
-
-        let mut max_geode = 0;
-
-        while let Some(state) = stack.pop() {
-            if !visited.contains(&state) {
-                if state.time <= 24 {
-                    for state in factory.next_states(&state) {
-                        stack.push(state);
-                    }
-                } else {
-                    max_geode = state.materials[3].max(max_geode);
-                }
-            }
-            visited.insert(state);
-        }
-
-        dbg!(&max_geode);
-
-        sum_quality_levels += factory.id * max_geode;
-    }
+    blueprints
+        .par_iter()
+        .map(|blueprint| blueprint.optimize(minutes))
+        .collect()
+}
 
-    sum_quality_levels.to_string()
+pub fn solve_part1(input: &str) -> String {
+    optimize(input, 24)
+        .iter()
+        .map(|plan| plan.quality_level())
+        .sum::<i32>()
+        .to_string()
 }
 
 pub fn solve_part2(input: &str) -> String {
-    let (_, factories) = parse(input).unwrap();
-
-    let factories = factories.iter().take(3).collect::<Vec<_>>();
+    let (_, blueprints) = parse(input).unwrap();
 
-    factories
+    blueprints
         .par_iter()
-        .map(|factory| {
-            let mut visited = HashSet::new();
-
-            let mut stack = BinaryHeap::from(vec![State::initial()]);
-
-            let mut max_geode = 0;
-
-            while let Some(state) = stack.pop() {
-                if !visited.contains(&state) {
-                    if state.time <= 32 {
-                        for state in factory.next_states(&state) {
-                            let time_left = 32 - state.time + 1;
-                            let geode_bound = state.materials[3]
-                                + state.robots[3] * time_left
-                                + time_left * (time_left) / 2;
-
-                            if geode_bound > max_geode {
-                                stack.push(state);
-                            } else {
-                                //   println!("removed a branch of time {}", &state.time)
-                            }
-                        }
-                    } else {
-                        max_geode = state.materials[3].max(max_geode);
-                        if max_geode == state.materials[3] {
-                            println!(
-                                "new max for factory {} found!! --> {}.",
-                                factory.id, max_geode
-                            )
-                        }
-                    }
-                }
-                visited.insert(state);
-            }
-
-            dbg!(&max_geode);
-
-            max_geode
-        })
+        .take(3)
+        .map(|blueprint| blueprint.optimize(32).collected)
         .product::<i32>()
         .to_string()
 }
@@ -276,10 +350,50 @@ mod tests {
         assert_eq!(result, "33");
     }
 
-    #[ignore = "not implemented"]
     #[test]
     fn part2_works() {
         let result = solve_part2(INPUT);
-        assert_eq!(result, "62");
+        assert_eq!(result, "3472");
+    }
+
+    #[test]
+    fn schedule_works() {
+        let plan = &optimize(INPUT, 24)[0];
+
+        let builds = plan
+            .schedule
+            .iter()
+            .enumerate()
+            .filter_map(|(minute, robot)| robot.map(|robot| (minute + 1, robot)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(plan.collected, 9);
+        assert_eq!(plan.to_string().lines().count(), 26);
+        assert_eq!(builds.iter().filter(|(_, robot)| *robot == 3).count(), 2);
+        assert!(builds.iter().all(|(minute, _)| *minute < 24));
+    }
+
+    #[test]
+    fn custom_resources_works() {
+        const INPUT: &str =
+            "Blueprint 1: Each wood robot costs 2 wood. Each gold robot costs 3 wood.";
+
+        let plan = &optimize(INPUT, 5)[0];
+
+        // wood robot at minute 3 does not pay off, a gold robot at minute 4 yields 1 gold
+        assert_eq!(plan.blueprint.resources, vec!["wood", "gold"]);
+        assert_eq!(plan.collected, 1);
+    }
+
+    #[test]
+    fn unknown_cost_fails() {
+        const INPUT: &str =
+            "Blueprint 1: Each wood robot costs 2 wood. Each gold robot costs 3 wood and 1 iron.";
+
+        let Err(nom::Err::Failure(error)) = parse(INPUT) else {
+            panic!("a cost in iron should not parse");
+        };
+
+        assert_eq!(error.input, "iron");
     }
 }
@@ -0,0 +1,28 @@
+use advent_of_code_2022::frames;
+use aoc_render::{save_png, Animation, Palette, GREEN, WHITE};
+use std::{env, fs};
+
+// usage: animate [rounds] [keep one frame every]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let rounds = args.get(1).map(|n| n.parse().unwrap()).unwrap_or(10);
+    let every = args.get(2).map(|n| n.parse().unwrap()).unwrap_or(1);
+
+    let palette = Palette::new(WHITE).tile('#', GREEN);
+
+    let frames = frames(&file, rounds);
+
+    let mut animation = Animation::new(palette.clone(), 4).delay(20).every(every);
+
+    for frame in &frames {
+        animation.push(frame);
+    }
+
+    animation.save_gif("elves.gif").unwrap();
+    save_png("elves.png", frames.last().unwrap(), &palette, 4).unwrap();
+
+    println!("{} frames saved to elves.gif", animation.len());
+}
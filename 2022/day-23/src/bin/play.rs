@@ -0,0 +1,15 @@
+use advent_of_code_2022::frames;
+use aoc_render::terminal::Player;
+use std::{env, fs};
+
+// usage: play [rounds] [frames per second]
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+
+    let args = env::args().collect::<Vec<_>>();
+
+    let rounds = args.get(1).map(|n| n.parse().unwrap()).unwrap_or(1000);
+    let fps = args.get(2).map(|n| n.parse().unwrap()).unwrap_or(10.0);
+
+    Player::new(fps).play(frames(&file, rounds)).unwrap();
+}
@@ -64,18 +64,33 @@ impl From<&str> for Map {
 
 impl Debug for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut output = "\n".to_string();
-
         let pad = 2;
 
+        let ((min_width, min_height), (max_width, max_height)) = self.bounds();
+
+        f.write_str(&self.render(
+            (min_width - pad, min_height - pad),
+            (max_width + pad, max_height + pad),
+        ))
+    }
+}
+
+impl Map {
+    fn bounds(&self) -> ((i32, i32), (i32, i32)) {
         let max_width = *self.elfs.keys().map(|(x, _)| x).max().unwrap();
         let min_width = *self.elfs.keys().map(|(x, _)| x).min().unwrap();
 
         let max_height = *self.elfs.keys().map(|(_, y)| y).max().unwrap();
         let min_height = *self.elfs.keys().map(|(_, y)| y).min().unwrap();
 
-        for y in (min_height - pad)..=(max_height + pad) {
-            for x in (min_width - pad)..=(max_width + pad) {
+        ((min_width, min_height), (max_width, max_height))
+    }
+
+    fn render(&self, min: (i32, i32), max: (i32, i32)) -> String {
+        let mut output = "\n".to_string();
+
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
                 match self.elfs.contains_key(&(x, y)) {
                     true => output += "#",
                     false => output += ".",
@@ -84,11 +99,9 @@ impl Debug for Map {
             output += "\n";
         }
 
-        f.write_str(&output)
+        output
     }
-}
 
-impl Map {
     fn freeze_isolated(&mut self) {
         let positions = self.elfs.keys().copied().collect::<Vec<_>>();
 
@@ -234,6 +247,43 @@ pub fn solve_part2(input: &str) -> String {
     map.ticks.to_string()
 }
 
+/// Elves after each of up to `rounds` rounds, stopping once none moves, all drawn over the
+/// area they ever cover so frames line up.
+pub fn frames(input: &str, rounds: usize) -> Vec<String> {
+    let mut map = Map::from(input);
+
+    let mut snapshots = vec![map.elfs.clone()];
+
+    for _ in 0..rounds {
+        let stable = map.tick();
+
+        snapshots.push(map.elfs.clone());
+
+        if stable {
+            break;
+        }
+    }
+
+    let (mut min, mut max) = map.bounds();
+
+    for elfs in &snapshots {
+        map.elfs = elfs.clone();
+
+        let (lower, upper) = map.bounds();
+
+        min = (min.0.min(lower.0), min.1.min(lower.1));
+        max = (max.0.max(upper.0), max.1.max(upper.1));
+    }
+
+    snapshots
+        .into_iter()
+        .map(|elfs| {
+            map.elfs = elfs;
+            map.render((min.0 - 1, min.1 - 1), (max.0 + 1, max.1 + 1))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,4 +301,18 @@ mod tests {
         let result = solve_part2(INPUT);
         assert_eq!(result, "20");
     }
+
+    #[test]
+    fn frames_works() {
+        let elves = INPUT.matches('#').count();
+
+        let frames = frames(INPUT, 100);
+
+        // elves stop moving on round 20
+        assert_eq!(frames.len(), 1 + 20);
+        assert!(frames.iter().all(|frame| frame.len() == frames[0].len()));
+        assert!(frames
+            .iter()
+            .all(|frame| frame.matches('#').count() == elves));
+    }
 }
@@ -1,9 +1,6 @@
 use itertools::Itertools;
 use num::Integer;
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 struct Strom {
@@ -11,12 +8,14 @@ struct Strom {
     dir: (i32, i32),
 }
 
+/// Blizzards of the valley, repeating every `lcm(width, height)` minutes.
 #[derive(Debug)]
-struct Valley {
+pub struct Valley {
     stroms: Vec<Strom>,
     width: i32,
     height: i32,
-    free_spots: HashMap<i32, HashSet<(i32, i32)>>,
+    period: i32,
+    free_spots: Vec<HashSet<(i32, i32)>>,
     all_locs: HashSet<(i32, i32)>,
 }
 
@@ -55,8 +54,9 @@ impl From<&str> for Valley {
             stroms,
             width,
             height,
+            period: width.lcm(&height),
             all_locs,
-            free_spots: HashMap::new(),
+            free_spots: Vec::new(),
         };
 
         valley.simulate();
@@ -66,25 +66,21 @@ impl From<&str> for Valley {
 }
 
 impl Valley {
-    fn simulate(&mut self) {
-        let period = self.width.lcm(&self.height);
-
-        for time in 1..=period {
-            let mut storm_locs = HashSet::with_capacity(self.stroms.len());
-
-            for i in 0..self.stroms.len() {
-                let loc = (
-                    (self.stroms[i].loc.0 + self.stroms[i].dir.0).rem_euclid(self.width),
-                    (self.stroms[i].loc.1 + self.stroms[i].dir.1).rem_euclid(self.height),
-                );
+    pub fn start(&self) -> (i32, i32) {
+        (0, -1)
+    }
 
-                self.stroms[i] = Strom {
-                    loc,
-                    dir: self.stroms[i].dir,
-                };
+    pub fn exit(&self) -> (i32, i32) {
+        (self.width - 1, self.height)
+    }
 
-                storm_locs.insert(loc);
-            }
+    fn simulate(&mut self) {
+        for _ in 0..self.period {
+            let storm_locs = self
+                .stroms
+                .iter()
+                .map(|strom| strom.loc)
+                .collect::<HashSet<_>>();
 
             let mut free_locs = self
                 .all_locs
@@ -92,235 +88,118 @@ impl Valley {
                 .copied()
                 .collect::<HashSet<_>>();
 
-            free_locs.insert((0, -1));
-            free_locs.insert((self.width - 1, self.height));
+            free_locs.insert(self.start());
+            free_locs.insert(self.exit());
 
-            self.free_spots.insert(time, free_locs);
-        }
-    }
+            self.free_spots.push(free_locs);
 
-    fn free_spots(&self, time: i32) -> HashSet<(i32, i32)> {
-        let mut time = time % self.width.lcm(&self.height);
-
-        if time == 0 {
-            time = self.width.lcm(&self.height)
+            for strom in self.stroms.iter_mut() {
+                strom.loc = (
+                    (strom.loc.0 + strom.dir.0).rem_euclid(self.width),
+                    (strom.loc.1 + strom.dir.1).rem_euclid(self.height),
+                );
+            }
         }
+    }
 
-        self.free_spots.get(&time).unwrap().clone()
+    fn free_spots(&self, time: i32) -> &HashSet<(i32, i32)> {
+        &self.free_spots[time.rem_euclid(self.period) as usize]
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct State {
-    position: (i32, i32),
-    time: i32,
-}
+    /// Earliest arrival at `to` leaving `from` at minute `time`, searching breadth first
+    /// over `(position, time mod period)`, the valley as a time-expanded graph.
+    pub fn leg(&self, from: (i32, i32), to: (i32, i32), time: i32) -> Option<Leg> {
+        let mut parents = HashMap::new();
+        parents.insert((from, time.rem_euclid(self.period)), from);
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (15 * self.position.0 + 2 * self.position.1)
-            .cmp(&(15 * other.position.0 + 2 * other.position.1))
-            .then_with(|| (self.time).cmp(&other.time))
-    }
-}
+        let mut frontier = vec![from];
+        let mut arrival = time;
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl State {
-    fn next_states(&self) -> Vec<Self> {
-        let time = self.time + 1;
-
-        vec![
-            Self {
-                position: (self.position.0 + 1, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1 + 1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0 - 1, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1 - 1),
-                time,
-            },
-        ]
-    }
-}
+        while !frontier.contains(&to) {
+            if frontier.is_empty() {
+                return None;
+            }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct StateReversed {
-    position: (i32, i32),
-    time: i32,
-}
+            arrival += 1;
 
-impl Ord for StateReversed {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (15 * other.position.0 + 2 * other.position.1)
-            .cmp(&(15 * self.position.0 + 2 * self.position.1))
-            .then_with(|| (self.time).cmp(&other.time))
-    }
-}
+            let free = self.free_spots(arrival);
+            let phase = arrival.rem_euclid(self.period);
 
-impl PartialOrd for StateReversed {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl StateReversed {
-    fn next_states(&self) -> Vec<Self> {
-        let time = self.time + 1;
-
-        vec![
-            Self {
-                position: (self.position.0 - 1, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1 - 1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0 + 1, self.position.1),
-                time,
-            },
-            Self {
-                position: (self.position.0, self.position.1 + 1),
-                time,
-            },
-        ]
-    }
-}
+            let mut next_frontier = vec![];
 
-fn find_min_time_forward(start: (i32, i32), exit: (i32, i32), time: i32, valley: &Valley) -> i32 {
-    let mut min_time = i32::MAX;
-
-    let initial_state = State {
-        position: start,
-        time,
-    };
-
-    let mut stack = BinaryHeap::from(vec![initial_state]);
-    let mut visited = HashSet::new();
-
-    while let Some(state) = stack.pop() {
-        if !visited.contains(&state) {
-            if state.position == exit {
-                min_time = state.time.min(min_time);
-                println!("> new min time {min_time}");
-            } else {
-                for state in state.next_states() {
-                    if valley.free_spots(state.time).contains(&state.position)
-                        && state.time
-                            + ((exit.0 - state.position.0).abs()
-                                + (exit.1 - state.position.1).abs())
-                            < min_time
-                        && !visited.contains(&State {
-                            position: state.position,
-                            time: state.time - valley.width.lcm(&valley.height),
-                        })
-                    {
-                        stack.push(state);
+            for position in frontier {
+                for (dx, dy) in [(1, 0), (0, 1), (0, 0), (-1, 0), (0, -1)] {
+                    let next = (position.0 + dx, position.1 + dy);
+
+                    if free.contains(&next) && !parents.contains_key(&(next, phase)) {
+                        parents.insert((next, phase), position);
+                        next_frontier.push(next);
                     }
                 }
             }
+
+            frontier = next_frontier;
         }
-        visited.insert(state);
-    }
-    min_time
-}
 
-fn find_min_time_backawards(
-    start: (i32, i32),
-    exit: (i32, i32),
-    time: i32,
-    valley: &Valley,
-) -> i32 {
-    let mut min_time = i32::MAX;
-
-    let initial_state = StateReversed {
-        position: start,
-        time,
-    };
-
-    let mut stack = BinaryHeap::from(vec![initial_state]);
-    let mut visited = HashSet::new();
-
-    while let Some(state) = stack.pop() {
-        if !visited.contains(&state) {
-            if state.position == exit {
-                min_time = state.time.min(min_time);
-                println!("> new min time {min_time}");
-            } else {
-                for state in state.next_states() {
-                    if valley.free_spots(state.time).contains(&state.position)
-                        && state.time
-                            + ((exit.0 - state.position.0).abs()
-                                + (exit.1 - state.position.1).abs())
-                            < min_time
-                        && !visited.contains(&StateReversed {
-                            position: state.position,
-                            time: state.time - valley.width.lcm(&valley.height),
-                        })
-                    {
-                        stack.push(state);
-                    }
-                }
-            }
+        let mut path = vec![to];
+
+        for t in (time + 1..=arrival).rev() {
+            let position = *path.last().unwrap();
+            path.push(*parents.get(&(position, t.rem_euclid(self.period))).unwrap());
         }
-        visited.insert(state);
+
+        path.reverse();
+
+        Some(Leg {
+            from,
+            to,
+            departure: time,
+            arrival,
+            path,
+        })
+    }
+
+    /// Visits the `waypoints` in order starting at minute `time`, `None` if one can not be reached.
+    pub fn route(&self, waypoints: &[(i32, i32)], time: i32) -> Option<Vec<Leg>> {
+        let mut time = time;
+
+        waypoints
+            .windows(2)
+            .map(|pair| {
+                let leg = self.leg(pair[0], pair[1], time)?;
+                time = leg.arrival;
+                Some(leg)
+            })
+            .collect()
     }
-    min_time
+}
+
+/// A trip between two waypoints, `path[i]` is the position at minute `departure + i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg {
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+    pub departure: i32,
+    pub arrival: i32,
+    pub path: Vec<(i32, i32)>,
 }
 
 pub fn solve_part1(input: &str) -> String {
     let valley = Valley::from(input);
 
-    let start = (0, -1);
-    let exit = (valley.width - 1, valley.height);
+    let legs = valley.route(&[valley.start(), valley.exit()], 0).unwrap();
 
-    let min_time = find_min_time_forward(start, exit, 0, &valley);
-
-    min_time.to_string()
+    legs.last().unwrap().arrival.to_string()
 }
 
 pub fn solve_part2(input: &str) -> String {
     let valley = Valley::from(input);
 
-    let start = (0, -1);
-    let exit = (valley.width - 1, valley.height);
-
-    let time = find_min_time_forward(start, exit, 0, &valley);
+    let (start, exit) = (valley.start(), valley.exit());
 
-    println!("one way took {time}");
+    let legs = valley.route(&[start, exit, start, exit], 0).unwrap();
 
-    let start = (valley.width - 1, valley.height);
-    let exit = (0, -1);
-
-    let time = find_min_time_backawards(start, exit, time, &valley);
-
-    println!("return took {time}");
-
-    let start = (0, -1);
-    let exit = (valley.width - 1, valley.height);
-
-    let time = find_min_time_forward(start, exit, time, &valley);
-
-    time.to_string()
+    legs.last().unwrap().arrival.to_string()
 }
 
 #[cfg(test)]
@@ -340,4 +219,29 @@ mod tests {
         let result = solve_part2(INPUT);
         assert_eq!(result, "54");
     }
+
+    #[test]
+    fn route_works() {
+        let valley = Valley::from(INPUT);
+
+        let (start, exit) = (valley.start(), valley.exit());
+
+        let legs = valley.route(&[start, exit, start, exit], 0).unwrap();
+
+        let arrivals = legs.iter().map(|leg| leg.arrival).collect::<Vec<_>>();
+        assert_eq!(arrivals, vec![18, 41, 54]);
+
+        for leg in legs {
+            assert_eq!(leg.path.first(), Some(&leg.from));
+            assert_eq!(leg.path.last(), Some(&leg.to));
+            assert_eq!(leg.path.len() as i32, leg.arrival - leg.departure + 1);
+
+            for (i, pair) in leg.path.windows(2).enumerate() {
+                let (a, b) = (pair[0], pair[1]);
+
+                assert!((a.0 - b.0).abs() + (a.1 - b.1).abs() <= 1);
+                assert!(valley.free_spots(leg.departure + i as i32 + 1).contains(&b));
+            }
+        }
+    }
 }
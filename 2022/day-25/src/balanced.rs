@@ -0,0 +1,422 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    iter::Sum,
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use num::{BigInt, Integer, ToPrimitive, Zero};
+
+/// The digits of a balanced base, from the most negative to the most positive. There is an odd
+/// number of them, and the one in the middle is zero.
+pub trait Digits: Eq {
+    const DIGITS: &'static str;
+}
+
+/// Base 5 with digits from -2 to 2, as the fuel requirements are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Snafu;
+
+impl Digits for Snafu {
+    const DIGITS: &'static str = "=-012";
+}
+
+/// Base 3 with digits from -1 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ternary;
+
+impl Digits for Ternary {
+    const DIGITS: &'static str = "-0+";
+}
+
+/// An integer of any size in the balanced base given by `R`.
+///
+/// Digits are kept least significant first and without leading zeros, so each number has a
+/// single representation and zero has no digits at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Balanced<R> {
+    digits: Vec<i64>,
+    radix: PhantomData<R>,
+}
+
+impl<R: Digits> Balanced<R> {
+    pub fn base() -> i64 {
+        let base = R::DIGITS.chars().count();
+        assert!(base % 2 == 1, "a balanced base has an odd number of digits");
+
+        base as i64
+    }
+
+    fn half() -> i64 {
+        Self::base() / 2
+    }
+
+    pub fn zero() -> Self {
+        Self::from_columns(vec![])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Digit values, least significant first.
+    pub fn digits(&self) -> &[i64] {
+        &self.digits
+    }
+
+    /// -1, 0 or 1, the sign of the leading digit.
+    pub fn signum(&self) -> i64 {
+        self.digits.last().map_or(0, |d| d.signum())
+    }
+
+    /// Carries columns holding any value, least significant first, until each is a digit.
+    fn from_columns(columns: Vec<i64>) -> Self {
+        let (base, half) = (Self::base(), Self::half());
+
+        let mut digits = Vec::with_capacity(columns.len() + 1);
+        let mut columns = columns.into_iter();
+        let mut carry = 0;
+
+        loop {
+            let value = match columns.next() {
+                Some(column) => column + carry,
+                None if carry != 0 => carry,
+                None => break,
+            };
+
+            let (mut digit, mut next) = (value.rem_euclid(base), value.div_euclid(base));
+
+            if digit > half {
+                digit -= base;
+                next += 1;
+            }
+
+            digits.push(digit);
+            carry = next;
+        }
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Balanced {
+            digits,
+            radix: PhantomData,
+        }
+    }
+}
+
+/// Numbers with more digits are further from zero, otherwise the first digit that differs
+/// decides as all the ones below it can not make up for it.
+impl<R: Digits> Ord for Balanced<R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (l, r) = (&self.digits, &other.digits);
+
+        match l.len().cmp(&r.len()) {
+            Ordering::Greater => self.signum().cmp(&0),
+            Ordering::Less => 0.cmp(&other.signum()),
+            Ordering::Equal => l.iter().rev().cmp(r.iter().rev()),
+        }
+    }
+}
+
+impl<R: Digits> PartialOrd for Balanced<R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: Digits> Neg for Balanced<R> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Balanced {
+            digits: self.digits.into_iter().map(|d| -d).collect(),
+            radix: PhantomData,
+        }
+    }
+}
+
+impl<R: Digits> Add for Balanced<R> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        [self, rhs].into_iter().sum()
+    }
+}
+
+impl<R: Digits> Sub for Balanced<R> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<R: Digits> Mul for Balanced<R> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::zero();
+        }
+
+        let mut columns = vec![0; self.digits.len() + rhs.digits.len()];
+
+        for (i, l) in self.digits.iter().enumerate() {
+            for (j, r) in rhs.digits.iter().enumerate() {
+                columns[i + j] += l * r;
+            }
+        }
+
+        Self::from_columns(columns)
+    }
+}
+
+/// Adds all the numbers digit by digit and carries once at the end.
+impl<R: Digits> Sum for Balanced<R> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut columns = vec![];
+
+        for n in iter {
+            if columns.len() < n.digits.len() {
+                columns.resize(n.digits.len(), 0);
+            }
+
+            columns.iter_mut().zip(n.digits).for_each(|(c, d)| *c += d);
+        }
+
+        Self::from_columns(columns)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    /// A character that is not in the alphabet, `position` counts characters from 0.
+    NotADigit {
+        position: usize,
+        found: char,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "a number needs at least one digit"),
+            ParseError::NotADigit { position, found } => {
+                write!(f, "`{found}` at position {position} is not a digit")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl<R: Digits> FromStr for Balanced<R> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let half = Self::half();
+
+        let columns = s
+            .chars()
+            .enumerate()
+            .map(
+                |(position, c)| match R::DIGITS.chars().position(|d| d == c) {
+                    Some(value) => Ok(value as i64 - half),
+                    None => Err(ParseError::NotADigit { position, found: c }),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_columns(columns.into_iter().rev().collect()))
+    }
+}
+
+impl<R: Digits> Display for Balanced<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let half = Self::half();
+        let digit = |d: i64| R::DIGITS.chars().nth((d + half) as usize).unwrap();
+
+        if self.is_zero() {
+            return write!(f, "{}", digit(0));
+        }
+
+        self.digits
+            .iter()
+            .rev()
+            .try_for_each(|d| write!(f, "{}", digit(*d)))
+    }
+}
+
+impl<R: Digits> From<i128> for Balanced<R> {
+    fn from(mut n: i128) -> Self {
+        let (base, half) = (Self::base() as i128, Self::half() as i128);
+        let mut digits = vec![];
+
+        // dividing first, `n - digit` overflows next to the bounds
+        while n != 0 {
+            let (mut digit, mut next) = (n.rem_euclid(base), n.div_euclid(base));
+
+            if digit > half {
+                digit -= base;
+                next += 1;
+            }
+
+            digits.push(digit as i64);
+            n = next;
+        }
+
+        Balanced {
+            digits,
+            radix: PhantomData,
+        }
+    }
+}
+
+/// The number does not fit in the integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "number out of range")
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+impl<R: Digits> TryFrom<&Balanced<R>> for i128 {
+    type Error = OutOfRange;
+
+    /// Goes through a [`BigInt`], the partial sums of a number that fits can still overflow.
+    fn try_from(n: &Balanced<R>) -> Result<Self, Self::Error> {
+        BigInt::from(n).to_i128().ok_or(OutOfRange)
+    }
+}
+
+impl<R: Digits> From<BigInt> for Balanced<R> {
+    fn from(mut n: BigInt) -> Self {
+        let (base, half) = (BigInt::from(Self::base()), Self::half());
+        let mut digits = vec![];
+
+        while !n.is_zero() {
+            let (mut next, digit) = n.div_mod_floor(&base);
+            let mut digit = digit.to_i64().unwrap();
+
+            if digit > half {
+                digit -= Self::base();
+                next += 1;
+            }
+
+            digits.push(digit);
+            n = next;
+        }
+
+        Balanced {
+            digits,
+            radix: PhantomData,
+        }
+    }
+}
+
+impl<R: Digits> From<&Balanced<R>> for BigInt {
+    fn from(n: &Balanced<R>) -> Self {
+        let base = Balanced::<R>::base();
+
+        n.digits
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, d| acc * base + *d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snafu_test() {
+        for (n, s) in [
+            (0, "0"),
+            (1, "1"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (20, "1-0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (-2022, "-2--1="),
+        ] {
+            let snafu = Balanced::<Snafu>::from(n);
+
+            assert_eq!(snafu.to_string(), s);
+            assert_eq!(s.parse(), Ok(snafu.clone()));
+            assert_eq!(i128::try_from(&snafu), Ok(n));
+        }
+
+        assert_eq!("002=".parse::<Balanced<Snafu>>().unwrap().to_string(), "2=");
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let t = |n: i128| Balanced::<Ternary>::from(n);
+
+        for a in -40..40 {
+            assert_eq!(-t(a), t(-a));
+
+            for b in -40..40 {
+                assert_eq!(t(a) + t(b), t(a + b));
+                assert_eq!(t(a) - t(b), t(a - b));
+                assert_eq!(t(a) * t(b), t(a * b));
+                assert_eq!(t(a).cmp(&t(b)), a.cmp(&b));
+            }
+        }
+
+        assert_eq!(t(-5).to_string(), "-++");
+        assert_eq!((1..=10).map(t).sum::<Balanced<Ternary>>(), t(55));
+    }
+
+    #[test]
+    fn conversions_test() {
+        let s = |n: i128| Balanced::<Snafu>::from(n);
+
+        for n in [i128::MAX, i128::MIN, i128::MIN + 1] {
+            assert_eq!(i128::try_from(&s(n)), Ok(n));
+        }
+
+        assert_eq!(i128::try_from(&(s(i128::MAX) + s(1))), Err(OutOfRange));
+
+        let big = BigInt::from(i128::MIN) * BigInt::from(i128::MAX) * BigInt::from(3);
+        let snafu = s(i128::MIN) * s(i128::MAX) * s(3);
+
+        assert_eq!(Balanced::<Snafu>::from(big.clone()), snafu);
+        assert_eq!(BigInt::from(&snafu), big);
+        assert!(snafu < s(i128::MIN));
+    }
+
+    #[test]
+    fn errors_test() {
+        assert_eq!(
+            "1=x".parse::<Balanced<Snafu>>(),
+            Err(ParseError::NotADigit {
+                position: 2,
+                found: 'x'
+            })
+        );
+        assert_eq!("".parse::<Balanced<Snafu>>(), Err(ParseError::Empty));
+        assert_eq!(
+            "+2".parse::<Balanced<Ternary>>().unwrap_err().to_string(),
+            "`2` at position 1 is not a digit"
+        );
+    }
+}
@@ -1,53 +1,11 @@
-fn snafu_dec(s: &str) -> i64 {
-    s.chars().rev().enumerate().fold(0, |acc, (i, c)| {
-        let v = match c {
-            '2' => 2,
-            '1' => 1,
-            '0' => 0,
-            '-' => -1,
-            '=' => -2,
-            _ => unreachable!(),
-        };
+pub mod balanced;
 
-        v * 5_i64.pow(i as u32) + acc
-    })
-}
-fn dec_snafu(d: i64) -> String {
-    let mut snafu = String::from("");
-    let mut rem = d;
-    let base = 5;
-
-    while rem > 0 {
-        let digit = rem.rem_euclid(base);
-
-        if digit < 3 {
-            snafu += &digit.to_string()
-        } else {
-            match digit {
-                3 => {
-                    snafu += "=";
-                }
-                4 => {
-                    snafu += "-";
-                }
-                _ => unreachable!(),
-            }
-
-            rem += base - digit;
-        }
-
-        rem /= base;
-    }
+use balanced::{Balanced, ParseError, Snafu};
 
-    snafu.chars().rev().collect()
-}
-
-pub fn solve_part1(input: &str) -> String {
-    let sum: i64 = input.lines().map(snafu_dec).sum();
-
-    assert_eq!(sum, snafu_dec(&dec_snafu(sum)));
+pub fn solve_part1(input: &str) -> Result<String, ParseError> {
+    let sum: Balanced<Snafu> = input.lines().map(str::parse).sum::<Result<_, _>>()?;
 
-    dec_snafu(sum)
+    Ok(sum.to_string())
 }
 
 pub fn solve_part2(input: &str) -> String {
@@ -63,10 +21,32 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = solve_part1(INPUT);
+        let result = solve_part1(INPUT).unwrap();
         assert_eq!(result, "2=-1=0");
     }
 
+    #[test]
+    fn part1_does_not_overflow() {
+        // each one is about 5^40 / 2, well past an i64
+        let input = vec!["2".repeat(40); 1000].join("\n");
+
+        let expected = (num::BigInt::from(5).pow(40) - 1) / 2 * 1000;
+
+        let result = solve_part1(&input).unwrap();
+        assert_eq!(
+            num::BigInt::from(&result.parse::<Balanced<Snafu>>().unwrap()),
+            expected
+        );
+
+        assert_eq!(
+            solve_part1("1=\n1x"),
+            Err(ParseError::NotADigit {
+                position: 1,
+                found: 'x'
+            })
+        );
+    }
+
     #[ignore = "not implemented"]
     #[test]
     fn part2_works() {
@@ -0,0 +1,302 @@
+/*
+ * Advent of code solutions
+ * https://www.github.com/scristobal/advent-of-code
+ * Licensed under MIT, 2023 Samuel Cristobal
+ */
+
+use aoc_intervals::{Interval, IntervalSet};
+use std::fmt::Display;
+
+use crate::part_1::{self, MapDef};
+
+/// A map from numbers to numbers that shifts each piece of its domain by a constant.
+///
+/// Pieces are `(start, shift)` sorted by start, the first one starts at 0 and each one runs up
+/// to the next. The last one is always the identity, as numbers past every source map to
+/// themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pieces: Vec<(u64, i64)>,
+}
+
+impl Mapping {
+    pub fn identity() -> Self {
+        Mapping {
+            pieces: vec![(0, 0)],
+        }
+    }
+
+    /// Map of the almanac `(dest, src, len)` triples, sources are assumed not to overlap.
+    pub fn from_triples(def: &[(u32, u32, u32)]) -> Self {
+        let mut sources = def
+            .iter()
+            .map(|&(dest, src, len)| {
+                (
+                    src as u64,
+                    src as u64 + len as u64,
+                    dest as i64 - src as i64,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        sources.sort();
+
+        let mut pieces = vec![];
+        let mut cursor = 0;
+
+        for (start, end, shift) in sources {
+            if cursor < start {
+                pieces.push((cursor, 0));
+            }
+
+            pieces.push((start, shift));
+            cursor = end;
+        }
+
+        pieces.push((cursor, 0));
+
+        Self::merge(pieces)
+    }
+
+    /// Drops empty pieces and joins neighbours with the same shift.
+    fn merge(pieces: Vec<(u64, i64)>) -> Self {
+        let mut res: Vec<(u64, i64)> = vec![];
+
+        for (start, shift) in pieces {
+            if res.last().is_some_and(|&(last, _)| last == start) {
+                res.pop();
+            }
+
+            if res.last().map_or(true, |&(_, last)| last != shift) {
+                res.push((start, shift));
+            }
+        }
+
+        Mapping { pieces: res }
+    }
+
+    /// Pieces as `(start, end, shift)`, the last one ends at `u64::MAX`.
+    ///
+    /// Ends are excluded, so `u64::MAX` itself is in no piece and [`Mapping::image`],
+    /// [`Mapping::preimage`] and [`Mapping::inverse`] leave it out. Almanac numbers fit in a
+    /// `u32`, and sums of two of them stay far below it.
+    fn bounds(&self) -> impl Iterator<Item = (u64, u64, i64)> + '_ {
+        self.pieces.iter().enumerate().map(|(i, &(start, shift))| {
+            let end = self.pieces.get(i + 1).map_or(u64::MAX, |next| next.0);
+            (start, end, shift)
+        })
+    }
+
+    pub fn apply(&self, n: u64) -> u64 {
+        let piece = self.pieces.partition_point(|&(start, _)| start <= n) - 1;
+
+        n.wrapping_add_signed(self.pieces[piece].1)
+    }
+
+    /// Map that sends `n` to `next.apply(self.apply(n))`.
+    pub fn then(&self, next: &Mapping) -> Self {
+        let mut pieces = vec![];
+
+        for (start, end, shift) in self.bounds() {
+            let (image_start, image_end) = (
+                start.wrapping_add_signed(shift),
+                end.wrapping_add_signed(shift),
+            );
+
+            for (next_start, next_end, next_shift) in next.bounds() {
+                let lo = image_start.max(next_start);
+                let hi = image_end.min(next_end);
+
+                if lo < hi {
+                    pieces.push((lo.wrapping_add_signed(-shift), shift + next_shift));
+                }
+            }
+        }
+
+        Self::merge(pieces)
+    }
+
+    /// Values of the numbers in `set`, all at once.
+    pub fn image(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.bounds()
+            .flat_map(|(start, end, shift)| {
+                set.intersection(&(start..end).into())
+                    .iter()
+                    .map(|interval| {
+                        interval.start.wrapping_add_signed(shift)
+                            ..interval.end.wrapping_add_signed(shift)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Numbers whose value lands in `set`.
+    pub fn preimage(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.bounds()
+            .flat_map(|(start, end, shift)| {
+                let image = start.wrapping_add_signed(shift)..end.wrapping_add_signed(shift);
+
+                set.intersection(&image.into())
+                    .iter()
+                    .map(|interval| {
+                        interval.start.wrapping_add_signed(-shift)
+                            ..interval.end.wrapping_add_signed(-shift)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// The map going back, `None` unless every number is the value of exactly one number.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut images = self
+            .bounds()
+            .map(|(start, end, shift)| {
+                (
+                    start.wrapping_add_signed(shift),
+                    end.wrapping_add_signed(shift),
+                    -shift,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        images.sort();
+
+        let mut cursor = 0;
+
+        for &(start, end, _) in &images {
+            if start != cursor {
+                return None;
+            }
+            cursor = end;
+        }
+
+        (cursor == u64::MAX).then(|| {
+            Self::merge(
+                images
+                    .into_iter()
+                    .map(|(start, _, shift)| (start, shift))
+                    .collect(),
+            )
+        })
+    }
+}
+
+/// Breakpoint table, one piece per line.
+impl Display for Mapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (start, end, shift) in self.bounds() {
+            let range = match end {
+                u64::MAX => format!("{start}.."),
+                end => format!("{start}..{end}"),
+            };
+
+            writeln!(f, "{range:<24} {shift:+}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// All the maps of the almanac chained into one, from seed to location.
+pub fn seed_to_location(maps: &[MapDef]) -> Mapping {
+    maps.iter()
+        .map(|def| Mapping::from_triples(def))
+        .fold(Mapping::identity(), |acc, map| acc.then(&map))
+}
+
+/// Seeds planted at any of the `locations`.
+pub fn seeds_at(
+    input: &'static str,
+    locations: impl Into<Interval<u64>>,
+) -> Result<IntervalSet<u64>, anyhow::Error> {
+    let (_, (_, maps)) = part_1::parse_input(input)?;
+
+    Ok(seed_to_location(&maps).preimage(&locations.into().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPS: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15";
+
+    #[test]
+    fn from_triples_test() {
+        let map = Mapping::from_triples(&[(50, 98, 2), (52, 50, 48)]);
+
+        assert_eq!(map.pieces, vec![(0, 0), (50, 2), (98, -48), (100, 0)]);
+        assert_eq!(map.apply(79), 81);
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(13), 13);
+        assert_eq!(map.to_string(), "0..50                    +0\n50..98                   +2\n98..100                  -48\n100..                    +0\n");
+    }
+
+    #[test]
+    fn then_test() {
+        let (_, (seeds, maps)) = part_1::parse_input(MAPS).unwrap();
+
+        let soil = Mapping::from_triples(&maps[0]);
+        let fertilizer = Mapping::from_triples(&maps[1]);
+        let both = soil.then(&fertilizer);
+
+        for n in 0..200 {
+            assert_eq!(both.apply(n), fertilizer.apply(soil.apply(n)));
+        }
+
+        for seed in seeds {
+            assert_eq!(
+                both.apply(seed as u64),
+                seed_to_location(&maps).apply(seed as u64)
+            );
+        }
+    }
+
+    #[test]
+    fn inverse_test() {
+        let (_, (_, maps)) = part_1::parse_input(MAPS).unwrap();
+
+        let map = seed_to_location(&maps);
+        let inverse = map.inverse().unwrap();
+
+        for n in 0..200 {
+            assert_eq!(inverse.apply(map.apply(n)), n);
+        }
+
+        assert_eq!(map.then(&inverse), Mapping::identity());
+
+        // 0 and 1 both land on 0
+        assert_eq!(Mapping::from_triples(&[(0, 1, 1)]).inverse(), None);
+    }
+
+    #[test]
+    fn image_test() {
+        let (_, (_, maps)) = part_1::parse_input(MAPS).unwrap();
+
+        let map = seed_to_location(&maps);
+        let seeds = IntervalSet::from_iter([79..93, 55..68]);
+
+        let image = map.image(&seeds);
+
+        assert_eq!(image.total_len(), seeds.total_len());
+        assert!((79..93).chain(55..68).all(|n| image.contains(map.apply(n))));
+        assert_eq!(map.preimage(&image), seeds);
+
+        let found = seeds_at(MAPS, 40..=41).unwrap();
+        assert!(found
+            .iter()
+            .flat_map(|i| i.start..i.end)
+            .all(|n| map.apply(n) / 2 == 20));
+        assert_eq!(found.total_len(), 2);
+    }
+}
@@ -0,0 +1,31 @@
+/*
+ * Advent of code solutions
+ * https://www.github.com/scristobal/advent-of-code
+ * Licensed under MIT, 2023 Samuel Cristobal
+ */
+
+use advent_of_code::{almanac, part_1};
+use std::error::Error;
+
+// usage: seeds [location] [last location]
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("../../input.txt");
+
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let (_, (_, maps)) = part_1::parse_input(input)?;
+    let map = almanac::seed_to_location(&maps);
+
+    println!("seed to location\n{map}");
+
+    if let Some(first) = args.get(1) {
+        let first: u64 = first.parse()?;
+        let last = args.get(2).map_or(Ok(first), |n| n.parse())?;
+
+        for seeds in almanac::seeds_at(input, first..=last)?.iter() {
+            println!("seeds {}..{}", seeds.start, seeds.end);
+        }
+    }
+
+    Ok(())
+}
@@ -4,6 +4,8 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
+pub mod almanac;
+
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -61,27 +63,15 @@ pub mod part_1 {
         tuple((parse_seeds, many0(preceded(parse_map_header, parse_map))))(input)
     }
 
-    pub fn map_factory(def: Vec<(u32, u32, u32)>) -> impl Fn(u32) -> u32 {
-        move |n| {
-            def.iter()
-                .filter(|(_, x, y)| x <= &n && n < x + y)
-                .map(|(z, x, _)| z + (n - x))
-                .min()
-                .unwrap_or(n)
-        }
-    }
-
     pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
-        let (input, result) = parse_input(input)?;
+        let (input, (seeds, maps)) = parse_input(input)?;
         assert!(input.is_empty());
 
-        let seeds = result.0;
-
-        let maps = result.1.into_iter().map(map_factory).collect::<Vec<_>>();
+        let map = almanac::seed_to_location(&maps);
 
         let res = seeds
             .into_iter()
-            .map(|s| maps.iter().fold(s, |acc, f| f(acc)))
+            .map(|s| map.apply(s as u64))
             .min()
             .unwrap();
 
@@ -92,6 +82,7 @@ pub mod part_1 {
 pub mod part_2 {
 
     use super::*;
+    use aoc_intervals::IntervalSet;
 
     pub type SeedsPairs = Vec<(u32, u32)>;
 
@@ -113,28 +104,21 @@ pub mod part_2 {
     }
 
     pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
-        let (input, result) = parse_input(input)?;
+        let (input, (seeds, maps)) = parse_input(input)?;
         assert!(input.is_empty());
 
-        let maps = result
-            .1
-            .into_iter()
-            .map(part_1::map_factory)
-            .collect::<Vec<_>>();
+        let seeds = seeds
+            .iter()
+            .map(|&(start, len)| start as u64..start as u64 + len as u64)
+            .collect::<IntervalSet<_>>();
 
-        let mut min = u32::MAX;
+        let locations = almanac::seed_to_location(&maps).image(&seeds);
 
-        for seed_range in &result.0 {
-            for seed in seed_range.0..(seed_range.0 + seed_range.1) {
-                let res = maps.iter().fold(seed, |acc, f| f(acc));
-                if res < min {
-                    min = res;
-                    println!("{} ", min)
-                }
-            }
-        }
+        let Some(min) = locations.iter().next() else {
+            anyhow::bail!("no seed ranges to find a location for");
+        };
 
-        Ok(min.to_string())
+        Ok(min.start.to_string())
     }
 }
 
@@ -242,4 +226,10 @@ humidity-to-location map:
         let result = part_2::solve(SAMPLE).unwrap();
         assert_eq!(result, "46");
     }
+
+    #[test]
+    fn part2_without_seeds_fails() {
+        let result = part_2::solve("seeds:\n\nseed-to-soil map:\n50 98 2");
+        assert!(result.is_err());
+    }
 }
@@ -4,7 +4,10 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
-use std::collections::HashMap;
+use aoc_graphs::{
+    petgraph::{graph::NodeIndex, visit::EdgeRef},
+    NamedDiGraph,
+};
 
 #[derive(PartialEq, Debug, Eq)]
 enum Direction {
@@ -12,12 +15,10 @@ enum Direction {
     Right,
 }
 
-type Node = [char; 3];
+/// Nodes by name, each one with an edge for every turn.
+type Network = NamedDiGraph<Direction>;
 
-type RightTurns = HashMap<Node, Node>;
-type LeftTurns = HashMap<Node, Node>;
-
-fn parse_input(input: &'static str) -> (Vec<Direction>, RightTurns, LeftTurns) {
+fn parse_input(input: &'static str) -> (Vec<Direction>, Network) {
     let (directions, turns) = input.split_once("\n\n").unwrap();
 
     let directions = directions
@@ -31,57 +32,48 @@ fn parse_input(input: &'static str) -> (Vec<Direction>, RightTurns, LeftTurns) {
         })
         .collect::<Vec<_>>();
 
-    let (right, left) = turns
-        .lines()
-        .map(|line| {
-            let (start, ends) = line.split_once(" = ").unwrap();
+    let mut network = Network::new();
 
-            let start_node = start.chars().take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+    for line in turns.lines() {
+        let (start, ends) = line.split_once(" = ").unwrap();
 
-            let (left, right) = ends.split_once(", ").unwrap();
+        let (left, right) = ends
+            .trim_matches(|c| c == '(' || c == ')')
+            .split_once(", ")
+            .unwrap();
 
-            let left_node = left.chars().skip(1).take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+        let (start, left, right) = (network.node(start), network.node(left), network.node(right));
 
-            let right_node = right.chars().take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+        // left and right can be the same node, so these are two edges
+        network.graph.add_edge(start, left, Direction::Left);
+        network.graph.add_edge(start, right, Direction::Right);
+    }
 
-            (start_node, (left_node, right_node))
-        })
-        .fold(
-            (HashMap::new(), HashMap::new()),
-            |(mut right, mut left), (start_node, (left_node, right_node))| {
-                right.insert(start_node, right_node);
-                left.insert(start_node, left_node);
-                (right, left)
-            },
-        );
+    (directions, network)
+}
 
-    (directions, right, left)
+fn turn(network: &Network, node: NodeIndex, direction: &Direction) -> NodeIndex {
+    network
+        .graph
+        .edges(node)
+        .find(|edge| edge.weight() == direction)
+        .unwrap()
+        .target()
 }
 
 pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
-    let (directions, right, left) = parse_input(input);
+    let (directions, network) = parse_input(input);
 
-    let mut current_node = ['A'; 3];
+    let mut current_node = network.index("AAA").unwrap();
+    let end = network.index("ZZZ").unwrap();
 
     let Some(steps) = directions
         .iter()
         .cycle()
         .enumerate()
         .find_map(|(index, direction)| {
-            current_node = match direction {
-                Direction::Left => *left.get(&current_node).unwrap(),
-                Direction::Right => *right.get(&current_node).unwrap(),
-            };
-            (current_node == ['Z'; 3]).then_some(index + 1)
+            current_node = turn(&network, current_node, direction);
+            (current_node == end).then_some(index + 1)
         })
     else {
         unreachable!()
@@ -102,37 +94,25 @@ ZZZ = (ZZZ, ZZZ)";
 
     #[test]
     fn parse_sample() {
-        let (directions, right, left) = parse_input(SAMPLE);
+        let (directions, network) = parse_input(SAMPLE);
 
         assert_eq!(
             directions,
             vec![Direction::Left, Direction::Left, Direction::Right]
         );
 
-        assert_eq!(right.len(), 3);
-        assert_eq!(left.len(), 3);
+        assert_eq!(network.graph.node_count(), 3);
 
-        assert_eq!(
-            left,
-            vec![
-                (['A', 'A', 'A'], ['B', 'B', 'B']),
-                (['B', 'B', 'B'], ['A', 'A', 'A']),
-                (['Z', 'Z', 'Z'], ['Z', 'Z', 'Z'])
-            ]
-            .into_iter()
-            .collect()
-        );
+        for (node, left, right) in [
+            ("AAA", "BBB", "BBB"),
+            ("BBB", "AAA", "ZZZ"),
+            ("ZZZ", "ZZZ", "ZZZ"),
+        ] {
+            let node = network.index(node).unwrap();
 
-        assert_eq!(
-            right,
-            vec![
-                (['A', 'A', 'A'], ['B', 'B', 'B']),
-                (['B', 'B', 'B'], ['Z', 'Z', 'Z']),
-                (['Z', 'Z', 'Z'], ['Z', 'Z', 'Z'])
-            ]
-            .into_iter()
-            .collect()
-        );
+            assert_eq!(network.name(turn(&network, node, &Direction::Left)), left);
+            assert_eq!(network.name(turn(&network, node, &Direction::Right)), right);
+        }
     }
 
     #[test]
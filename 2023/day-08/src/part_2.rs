@@ -4,6 +4,10 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
+use aoc_graphs::{
+    petgraph::{graph::NodeIndex, visit::EdgeRef},
+    NamedDiGraph,
+};
 use std::collections::HashMap;
 
 use num::integer;
@@ -14,12 +18,10 @@ enum Direction {
     Right,
 }
 
-type Node = [char; 3];
+/// Nodes by name, each one with an edge for every turn.
+type Network = NamedDiGraph<Direction>;
 
-type RightTurns = HashMap<Node, Node>;
-type LeftTurns = HashMap<Node, Node>;
-
-fn parse_input(input: &'static str) -> (Vec<Direction>, Vec<Node>, RightTurns, LeftTurns) {
+fn parse_input(input: &'static str) -> (Vec<Direction>, Vec<NodeIndex>, Network) {
     let (directions, turns) = input.split_once("\n\n").unwrap();
 
     let directions = directions
@@ -33,48 +35,42 @@ fn parse_input(input: &'static str) -> (Vec<Direction>, Vec<Node>, RightTurns, L
         })
         .collect::<Vec<_>>();
 
-    let (nodes, right, left) = turns
-        .lines()
-        .map(|line| {
-            let (start, ends) = line.split_once(" = ").unwrap();
+    let mut nodes = vec![];
+    let mut network = Network::new();
 
-            let start_node = start.chars().take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+    for line in turns.lines() {
+        let (start, ends) = line.split_once(" = ").unwrap();
 
-            let (left, right) = ends.split_once(", ").unwrap();
+        let (left, right) = ends
+            .trim_matches(|c| c == '(' || c == ')')
+            .split_once(", ")
+            .unwrap();
 
-            let left_node = left.chars().skip(1).take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+        if start.ends_with('A') {
+            nodes.push(network.node(start));
+        }
 
-            let right_node = right.chars().take(3).fold([' '; 3], |mut acc, c| {
-                acc[acc.iter().position(|&x| x == ' ').unwrap()] = c;
-                acc
-            });
+        let (start, left, right) = (network.node(start), network.node(left), network.node(right));
 
-            (start_node, (left_node, right_node))
-        })
-        .fold(
-            (Vec::new(), HashMap::new(), HashMap::new()),
-            |(mut nodes, mut right, mut left), (start_node, (left_node, right_node))| {
-                if start_node[2] == 'A' {
-                    nodes.push(start_node);
-                };
-
-                right.insert(start_node, right_node);
-                left.insert(start_node, left_node);
-                (nodes, right, left)
-            },
-        );
+        // left and right can be the same node, so these are two edges
+        network.graph.add_edge(start, left, Direction::Left);
+        network.graph.add_edge(start, right, Direction::Right);
+    }
 
-    (directions, nodes, right, left)
+    (directions, nodes, network)
+}
+
+fn turn(network: &Network, node: NodeIndex, direction: &Direction) -> NodeIndex {
+    network
+        .graph
+        .edges(node)
+        .find(|edge| edge.weight() == direction)
+        .unwrap()
+        .target()
 }
 
 pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
-    let (directions, mut nodes, right, left) = parse_input(input);
+    let (directions, mut nodes, network) = parse_input(input);
 
     let steps = nodes
         .iter_mut()
@@ -83,10 +79,7 @@ pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
 
             directions.iter().enumerate().cycle().enumerate().find_map(
                 |(steps, (index, direction))| {
-                    *node = match direction {
-                        Direction::Left => *left.get(node).unwrap(),
-                        Direction::Right => *right.get(node).unwrap(),
-                    };
+                    *node = turn(&network, *node, direction);
 
                     match visited.get(&(*node, index)) {
                         Some(prev_steps) => Some(steps - *prev_steps),
@@ -120,42 +113,30 @@ XXX = (XXX, XXX)";
 
     #[test]
     fn parse_sample() {
-        let (directions, nodes, right, left) = parse_input(SAMPLE);
+        let (directions, nodes, network) = parse_input(SAMPLE);
 
         assert_eq!(directions, vec![Direction::Left, Direction::Right]);
 
-        assert_eq!(nodes, vec![['1', '1', 'A'], ['2', '2', 'A']]);
-
         assert_eq!(
-            left,
-            vec![
-                (['1', '1', 'A'], ['1', '1', 'B']),
-                (['2', '2', 'A'], ['2', '2', 'B']),
-                (['1', '1', 'B'], ['X', 'X', 'X']),
-                (['2', '2', 'B'], ['2', '2', 'C']),
-                (['2', '2', 'C'], ['2', '2', 'Z']),
-                (['2', '2', 'Z'], ['2', '2', 'B']),
-                (['X', 'X', 'X'], ['X', 'X', 'X']),
-                (['1', '1', 'Z'], ['1', '1', 'B'])
-            ]
-            .into_iter()
-            .collect()
-        );
-        assert_eq!(
-            right,
-            vec![
-                (['1', '1', 'A'], ['X', 'X', 'X']),
-                (['2', '2', 'A'], ['X', 'X', 'X']),
-                (['1', '1', 'B'], ['1', '1', 'Z']),
-                (['2', '2', 'B'], ['2', '2', 'C']),
-                (['2', '2', 'C'], ['2', '2', 'Z']),
-                (['2', '2', 'Z'], ['2', '2', 'B']),
-                (['X', 'X', 'X'], ['X', 'X', 'X']),
-                (['1', '1', 'Z'], ['X', 'X', 'X'])
-            ]
-            .into_iter()
-            .collect()
+            nodes.iter().map(|&n| network.name(n)).collect::<Vec<_>>(),
+            vec!["11A", "22A"]
         );
+
+        for (node, left, right) in [
+            ("11A", "11B", "XXX"),
+            ("22A", "22B", "XXX"),
+            ("11B", "XXX", "11Z"),
+            ("22B", "22C", "22C"),
+            ("22C", "22Z", "22Z"),
+            ("22Z", "22B", "22B"),
+            ("XXX", "XXX", "XXX"),
+            ("11Z", "11B", "XXX"),
+        ] {
+            let node = network.index(node).unwrap();
+
+            assert_eq!(network.name(turn(&network, node, &Direction::Left)), left);
+            assert_eq!(network.name(turn(&network, node, &Direction::Right)), right);
+        }
     }
 
     #[test]
@@ -7,6 +7,7 @@
 use std::{fmt::Display, str::FromStr};
 
 use anyhow::Error;
+use aoc_geometry::{Point, Polygon};
 use itertools::Itertools;
 
 #[derive(Debug)]
@@ -138,132 +139,16 @@ impl Grid {
         })
     }
 
-    fn erase(&mut self, main_loop: &[State]) {
-        let main_loop = main_loop
-            .iter()
-            .map(|State { position, .. }| position)
-            .collect_vec();
-
-        for (i, pipe) in self.pipes.iter_mut().enumerate() {
-            if !main_loop.contains(&&i) && *pipe != ' ' {
-                *pipe = '.';
-            }
-            // if main_loop.contains(&&i) {
-            //     // *pipe = '*';
-            // }
-        }
-    }
-
-    fn mark_inside(&mut self, main_loop: &Vec<State>) -> usize {
-        for state in main_loop {
-            let right_adjacents = match state.direction {
-                'n' => [
-                    state.position - self.width + 1,
-                    state.position + 1,
-                    state.position + self.width + 1,
-                ],
-                's' => [
-                    state.position - self.width - 1,
-                    state.position - 1,
-                    state.position + self.width - 1,
-                ],
-                'e' => [
-                    state.position + self.width - 1,
-                    state.position + self.width,
-                    state.position + self.width + 1,
-                ],
-                'w' => [
-                    state.position - self.width - 1,
-                    state.position - self.width,
-                    state.position - self.width + 1,
-                ],
-                _ => unreachable!(),
-            };
-
-            for right_adjacent in right_adjacents {
-                if self.pipes[right_adjacent] == '.' {
-                    self.flood(right_adjacent, 'A');
-                }
-            }
-
-            let left_adjacent = match state.direction {
-                'n' => [
-                    state.position - self.width - 1,
-                    state.position - 1,
-                    state.position + self.width - 1,
-                ],
-                's' => [
-                    state.position - self.width + 1,
-                    state.position + 1,
-                    state.position + self.width + 1,
-                ],
-                'e' => [
-                    state.position - self.width - 1,
-                    state.position - self.width,
-                    state.position - self.width + 1,
-                ],
-                'w' => [
-                    state.position + self.width - 1,
-                    state.position + self.width,
-                    state.position + self.width + 1,
-                ],
-                _ => unreachable!(),
-            };
-
-            for left_adjacent in left_adjacent {
-                if self.pipes[left_adjacent] == '.' {
-                    self.flood(left_adjacent, 'B');
-                }
-            }
-        }
-
-        let outside = self.pipes[self.width + 1];
-
-        let inside = match outside {
-            'A' => 'B',
-            'B' => 'A',
-            _ => panic!("Invalid outside pipe"),
-        };
-
-        self.pipes.iter_mut().for_each(|p| {
-            if *p == outside {
-                *p = 'O';
-            } else if *p == inside {
-                *p = 'I';
-            }
-        });
-
-        self.pipes.iter().filter(|&p| *p == 'I').count()
-    }
-
-    fn flood(&mut self, position: usize, pipe: char) {
-        self.pipes[position] = pipe;
-
-        let mut queued = vec![position];
-
-        while let Some(position) = queued.pop() {
-            if self.pipes[position + 1] == '.' {
-                queued.push(position + 1);
-                self.pipes[position + 1] = pipe;
-            }
-            if self.pipes[position - 1] == '.' {
-                queued.push(position - 1);
-                self.pipes[position - 1] = pipe;
-            }
-            if self.pipes[position + self.width] == '.' {
-                queued.push(position + self.width);
-                self.pipes[position + self.width] = pipe;
-            }
-            if self.pipes[position - self.width] == '.' {
-                queued.push(position - self.width);
-                self.pipes[position - self.width] = pipe;
-            }
-        }
+    fn coords(&self, position: usize) -> Point {
+        (
+            (position % self.width) as i64,
+            (position / self.width) as i64,
+        )
     }
 }
 
 pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
-    let mut grid = Grid::from_str(input)?;
+    let grid = Grid::from_str(input)?;
 
     let mut state = grid.initial_state();
 
@@ -274,11 +159,15 @@ pub fn solve(input: &'static str) -> Result<String, anyhow::Error> {
         main_loop.push(next_state);
     }
 
-    grid.erase(&main_loop);
-
-    let insiders = grid.mark_inside(&main_loop);
+    // tiles on the loop are the boundary points, the enclosed ones follow from Pick's theorem
+    let main_loop = Polygon::new(
+        main_loop
+            .iter()
+            .map(|state| grid.coords(state.position))
+            .collect(),
+    );
 
-    println!("{}", &grid);
+    let insiders = main_loop.interior_points();
 
     Ok(insiders.to_string())
 }
@@ -0,0 +1,35 @@
+/*
+ * Advent of code solutions
+ * https://www.github.com/scristobal/advent-of-code
+ * Licensed under MIT, 2023 Samuel Cristobal
+ */
+
+use advent_of_code::part_2;
+use aoc_render::{save_png, Animation, Palette, BROWN, GRAY, WHITE};
+use std::error::Error;
+
+// usage: animate [spin cycles] [keep one frame every]
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("../../input.txt");
+
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let cycles = args.get(1).map_or(Ok(3), |n| n.parse())?;
+    let every = args.get(2).map_or(Ok(1), |n| n.parse())?;
+
+    let palette = Palette::new(WHITE).tile('#', GRAY).tile('O', BROWN);
+
+    let mut animation = Animation::new(palette.clone(), 6).delay(25).every(every);
+    let mut last = String::new();
+
+    for frame in part_2::frames(input, cycles)? {
+        animation.push(&frame);
+        last = frame;
+    }
+
+    animation.save_gif("platform.gif")?;
+    save_png("platform.png", &last, &palette, 6)?;
+
+    println!("{} frames saved to platform.gif", animation.len());
+    Ok(())
+}
@@ -186,6 +186,27 @@ impl Platform {
     }
 }
 
+/// Platform before spinning and after every tilt of `cycles` spin cycles.
+pub fn frames(input: &str, cycles: usize) -> Result<impl Iterator<Item = String>, Error> {
+    let mut platform = Platform::from_str(input)?;
+
+    let initial = platform.to_string();
+
+    let tilts: [fn(&mut Platform); 4] = [
+        Platform::tilt_north,
+        Platform::tilt_west,
+        Platform::tilt_south,
+        Platform::tilt_east,
+    ];
+
+    let frames = (0..cycles).flat_map(move |_| tilts).map(move |tilt| {
+        tilt(&mut platform);
+        platform.to_string()
+    });
+
+    Ok(std::iter::once(initial).chain(frames))
+}
+
 pub fn solve(input: &'static str) -> String {
     let mut platform = Platform::from_str(input).unwrap();
 
@@ -270,4 +291,25 @@ O.#..O.#.#
         let result = solve(SAMPLE);
         assert_eq!(result, "64");
     }
+
+    #[test]
+    fn frames_sample() {
+        let frames = frames(SAMPLE, 1).unwrap().collect::<Vec<_>>();
+
+        assert_eq!(frames.len(), 1 + 4);
+
+        #[rustfmt::skip]
+        assert_eq!(frames[4],
+".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+");
+    }
 }
@@ -4,12 +4,12 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
-use itertools::Itertools;
+use aoc_geometry::Polygon;
 
 #[derive(Debug)]
 struct Instruction {
     dir: char,
-    len: i128,
+    len: i64,
 }
 
 fn parse(input: &str) -> Vec<Instruction> {
@@ -28,7 +28,7 @@ fn parse(input: &str) -> Vec<Instruction> {
                 _ => panic!(),
             };
 
-            let len = i128::from_str_radix(&bugged.chars().skip(2).take(5).collect::<String>(), 16)
+            let len = i64::from_str_radix(&bugged.chars().skip(2).take(5).collect::<String>(), 16)
                 .unwrap();
 
             Instruction { dir, len }
@@ -36,42 +36,21 @@ fn parse(input: &str) -> Vec<Instruction> {
         .collect()
 }
 
-fn solve_shoelace(instructions: &[Instruction]) -> i128 {
-    let mut cur = (0_i128, 0_i128);
-
-    let pivots: Vec<_> = instructions
-        .iter()
-        .map(|ins| {
-            cur = match ins.dir {
-                'U' => (cur.0 - ins.len, cur.1),
-                'D' => (cur.0 + ins.len, cur.1),
-                'L' => (cur.0, cur.1 - ins.len),
-                'R' => (cur.0, cur.1 + ins.len),
-                _ => panic!(),
-            };
-
-            cur
-        })
-        .collect();
-
-    let pivots: Vec<_> = pivots.iter().chain(pivots.first()).collect();
-
-    // Shoelace (Trapezoid) formula
-    let area = pivots
-        .iter()
-        .tuple_windows()
-        .fold(0_i128, |acc, (p, q)| acc + ((p.0 + q.0) * (p.1 - q.1)))
-        / 2;
-
-    let num_border: i128 = instructions
-        .iter()
-        .take(instructions.len())
-        .fold(0, |acc, item| acc + item.len);
-
-    // inverse of Pick's Theorem
-    let num_inside = area - (num_border / 2) + 1;
-
-    num_inside + num_border
+fn solve_shoelace(instructions: &[Instruction]) -> i64 {
+    let polygon = Polygon::from_steps(instructions.iter().map(|ins| {
+        let dir = match ins.dir {
+            'U' => (-1, 0),
+            'D' => (1, 0),
+            'L' => (0, -1),
+            'R' => (0, 1),
+            _ => panic!(),
+        };
+
+        (dir, ins.len)
+    }));
+
+    // the trench is as wide as a cube, so it counts along with the cubes it encloses
+    polygon.lattice_points()
 }
 
 pub fn solve(input: &str) -> String {
@@ -4,6 +4,7 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
+use aoc_intervals::IntervalSet;
 use nom::character::complete;
 use nom::multi::{separated_list0, separated_list1};
 use nom::sequence::{delimited, preceded};
@@ -147,17 +148,33 @@ fn parse_input(input: &str) -> IResult<&str, ProcessingPlant> {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Range {
-    min: u128,
-    max: u128,
+struct Restriction {
+    range_x: IntervalSet<u128>,
+    range_m: IntervalSet<u128>,
+    range_a: IntervalSet<u128>,
+    range_s: IntervalSet<u128>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-struct Restriction {
-    range_x: Range,
-    range_m: Range,
-    range_a: Range,
-    range_s: Range,
+impl Restriction {
+    fn range_mut(&mut self, field: Field) -> &mut IntervalSet<u128> {
+        match field {
+            X => &mut self.range_x,
+            M => &mut self.range_m,
+            A => &mut self.range_a,
+            S => &mut self.range_s,
+        }
+    }
+
+    fn combinations(&self) -> u128 {
+        self.range_x.total_len()
+            * self.range_m.total_len()
+            * self.range_a.total_len()
+            * self.range_s.total_len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.combinations() == 0
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -174,10 +191,10 @@ pub fn solve(input: &str) -> String {
         classification: Continue("in".to_string()),
         workflow_history: vec![],
         restriction: Restriction {
-            range_x: Range { min: 1, max: 4000 },
-            range_m: Range { min: 1, max: 4000 },
-            range_a: Range { min: 1, max: 4000 },
-            range_s: Range { min: 1, max: 4000 },
+            range_x: IntervalSet::from(1..=4000),
+            range_m: IntervalSet::from(1..=4000),
+            range_a: IntervalSet::from(1..=4000),
+            range_s: IntervalSet::from(1..=4000),
         },
     }];
 
@@ -198,70 +215,29 @@ pub fn solve(input: &str) -> String {
                     .get(&workflow_id)
                     .unwrap_or_else(|| panic!("Failed to find workflow {}", workflow_id));
 
-                let mut restriction = state.restriction.clone();
                 let mut negated_restriction = state.restriction.clone();
 
                 for rule in &workflow.rules {
-                    match (rule.field, rule.op) {
-                        (X, cmp::Ordering::Greater) => {
-                            restriction.range_x.min =
-                                cmp::max(restriction.range_x.min, rule.value + 1);
-                            negated_restriction.range_x.max =
-                                cmp::min(negated_restriction.range_x.max, rule.value);
-                        }
-                        (X, cmp::Ordering::Less) => {
-                            restriction.range_x.max =
-                                cmp::min(restriction.range_x.max, rule.value - 1);
-                            negated_restriction.range_x.min =
-                                cmp::max(negated_restriction.range_x.min, rule.value);
-                        }
-                        (M, cmp::Ordering::Greater) => {
-                            restriction.range_m.min =
-                                cmp::max(restriction.range_m.min, rule.value + 1);
-                            negated_restriction.range_m.max =
-                                cmp::min(negated_restriction.range_m.max, rule.value);
-                        }
-                        (M, cmp::Ordering::Less) => {
-                            restriction.range_m.max =
-                                cmp::min(restriction.range_m.max, rule.value - 1);
-                            negated_restriction.range_m.min =
-                                cmp::max(negated_restriction.range_m.min, rule.value);
-                        }
-                        (A, cmp::Ordering::Greater) => {
-                            restriction.range_a.min =
-                                cmp::max(restriction.range_a.min, rule.value + 1);
-                            negated_restriction.range_a.max =
-                                cmp::min(negated_restriction.range_a.max, rule.value);
-                        }
-                        (A, cmp::Ordering::Less) => {
-                            restriction.range_a.max =
-                                cmp::min(restriction.range_a.max, rule.value - 1);
-                            negated_restriction.range_a.min =
-                                cmp::max(negated_restriction.range_a.min, rule.value);
-                        }
-                        (S, cmp::Ordering::Greater) => {
-                            restriction.range_s.min =
-                                cmp::max(restriction.range_s.min, rule.value + 1);
-                            negated_restriction.range_s.max =
-                                cmp::min(negated_restriction.range_s.max, rule.value);
-                        }
-                        (S, cmp::Ordering::Less) => {
-                            restriction.range_s.max =
-                                cmp::min(restriction.range_s.max, rule.value - 1);
-                            negated_restriction.range_s.min =
-                                cmp::max(negated_restriction.range_s.min, rule.value);
+                    let range = negated_restriction.range_mut(rule.field);
+
+                    let (matching, rest) = match rule.op {
+                        cmp::Ordering::Less => range.split_at(rule.value),
+                        cmp::Ordering::Greater => {
+                            let (below, above) = range.split_at(rule.value + 1);
+                            (above, below)
                         }
                         _ => panic!("Invalid rule {:?}", rule),
-                    }
+                    };
+
+                    *range = rest;
 
-                    if restriction.range_x.min <= restriction.range_x.max
-                        && restriction.range_m.min <= restriction.range_m.max
-                        && restriction.range_a.min <= restriction.range_a.max
-                        && restriction.range_s.min <= restriction.range_s.max
-                    {
+                    let mut restriction = negated_restriction.clone();
+                    *restriction.range_mut(rule.field) = matching;
+
+                    if !restriction.is_empty() {
                         queue.push(State {
                             classification: rule.success.clone(),
-                            restriction: restriction.clone(),
+                            restriction,
                             workflow_history: state
                                 .workflow_history
                                 .iter()
@@ -270,15 +246,9 @@ pub fn solve(input: &str) -> String {
                                 .collect(),
                         });
                     }
-
-                    restriction = negated_restriction.clone()
                 }
 
-                if negated_restriction.range_x.min <= negated_restriction.range_x.max
-                    && negated_restriction.range_m.min <= negated_restriction.range_m.max
-                    && negated_restriction.range_a.min <= negated_restriction.range_a.max
-                    && negated_restriction.range_s.min <= negated_restriction.range_s.max
-                {
+                if !negated_restriction.is_empty() {
                     queue.push(State {
                         classification: workflow.fall_back.clone(),
                         restriction: negated_restriction,
@@ -296,22 +266,12 @@ pub fn solve(input: &str) -> String {
 
     let num_accepted = accepted
         .iter()
-        .map(|State { restriction: r, .. }| {
-            (1 + r.range_x.max - r.range_x.min)
-                * (1 + r.range_m.max - r.range_m.min)
-                * (1 + r.range_a.max - r.range_a.min)
-                * (1 + r.range_s.max - r.range_s.min)
-        })
+        .map(|State { restriction, .. }| restriction.combinations())
         .sum::<u128>();
 
     let num_rejected = rejected
         .iter()
-        .map(|State { restriction: r, .. }| {
-            (1 + r.range_x.max - r.range_x.min)
-                * (1 + r.range_m.max - r.range_m.min)
-                * (1 + r.range_a.max - r.range_a.min)
-                * (1 + r.range_s.max - r.range_s.min)
-        })
+        .map(|State { restriction, .. }| restriction.combinations())
         .sum::<u128>();
 
     assert_eq!(4000 * 4000 * 4000 * 4000, num_accepted + num_rejected);
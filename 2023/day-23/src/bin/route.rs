@@ -0,0 +1,12 @@
+/*
+ * Advent of code solutions
+ * https://www.github.com/scristobal/advent-of-code
+ * Licensed under MIT, 2023 Samuel Cristobal
+ */
+
+use advent_of_code::part_2;
+
+fn main() {
+    let input = include_str!("../../input.txt");
+    print!("{}", part_2::render(input));
+}
@@ -5,10 +5,12 @@
  */
 
 use glam::IVec2;
-use petgraph::algo;
-use petgraph::graph::DiGraph;
+use petgraph::graph::{EdgeIndex, NodeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
     fmt::Display,
     str::FromStr,
 };
@@ -84,7 +86,42 @@ impl Display for Env {
     }
 }
 
+/// A corridor between two junctions, `cells` runs from one end to the other, both included.
+#[derive(Debug, Clone)]
+struct Corridor {
+    cells: Vec<IVec2>,
+}
+
+impl Corridor {
+    fn length(&self) -> usize {
+        self.cells.len() - 1
+    }
+}
+
+/// The maze compressed to its junctions, plus start and goal, joined by corridors.
+struct Junctions {
+    graph: UnGraph<IVec2, Corridor>,
+    start: NodeIndex,
+    goal: NodeIndex,
+}
+
+/// Longest route from start to goal, through the junctions and every tile walked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub length: usize,
+    pub junctions: Vec<IVec2>,
+    pub cells: Vec<IVec2>,
+}
+
 impl Env {
+    fn start(&self) -> IVec2 {
+        IVec2::new(1, 0)
+    }
+
+    fn goal(&self) -> IVec2 {
+        IVec2::new(self.size.x - 2, self.size.y - 1)
+    }
+
     fn adjacents(&self, p: &IVec2) -> Vec<(IVec2, usize)> {
         [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y]
             .iter()
@@ -99,110 +136,291 @@ impl Env {
             .collect()
     }
 
-    fn fast_forward(&self, p: &IVec2, d: &IVec2) -> (IVec2, usize) {
-        let mut p = *p;
-        let mut d = *d;
-        let mut dist = 0;
+    fn is_junction(&self, p: &IVec2) -> bool {
+        *p == self.start() || *p == self.goal() || self.adjacents(p).len() > 2
+    }
+
+    /// Walks from `p` into `q` and on until there is a choice to make or a dead end.
+    fn corridor(&self, p: &IVec2, q: &IVec2) -> Corridor {
+        let mut cells = vec![*p, *q];
 
         loop {
-            let adjacents: Vec<_> = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y]
-                .iter()
-                .filter_map(|&q_d| {
-                    if (q_d + d) == IVec2::ZERO {
-                        return None;
-                    }
+            let last = cells[cells.len() - 1];
+            let previous = cells[cells.len() - 2];
 
-                    let q = p + q_d;
+            if self.is_junction(&last) {
+                break;
+            }
 
-                    match self.tiles.get(&q) {
-                        Some(Path) => Some((q, q_d, dist + 1)),
-                        _ => None,
-                    }
-                })
-                .collect();
+            let onwards = self
+                .adjacents(&last)
+                .into_iter()
+                .filter(|(r, _)| *r != previous)
+                .collect::<Vec<_>>();
 
-            if adjacents.len() == 1 {
-                (p, d, dist) = *adjacents.first().unwrap();
-            } else {
-                break (p, dist);
+            if onwards.len() != 1 {
+                break;
             }
+
+            cells.push(onwards[0].0);
         }
+
+        Corridor { cells }
     }
 
-    fn adjacents_fast_forwarded(&self, p: &IVec2) -> Vec<(IVec2, usize)> {
-        [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y]
+    fn junctions(&self) -> Junctions {
+        let mut graph = UnGraph::<IVec2, Corridor>::new_undirected();
+
+        let mut nodes = self
+            .tiles
             .iter()
-            .filter_map(|&d| {
-                let q = *p + d;
+            .filter(|(p, tile)| matches!(tile, Path) && self.is_junction(p))
+            .map(|(p, _)| *p)
+            .collect::<Vec<_>>();
 
-                match self.tiles.get(&q) {
-                    Some(Path) => {
-                        Some((self.fast_forward(&q, &d).0, self.fast_forward(&q, &d).1 + 1))
+        nodes.sort_by_key(|p| (p.y, p.x));
+
+        let index = nodes
+            .into_iter()
+            .map(|p| (p, graph.add_node(p)))
+            .collect::<HashMap<_, _>>();
+
+        for (p, &n) in &index {
+            for (q, _) in self.adjacents(p) {
+                let corridor = self.corridor(p, &q);
+
+                // dead ends and the way back to the same junction are never part of a route
+                if let Some(&m) = index.get(corridor.cells.last().unwrap()) {
+                    if n < m {
+                        graph.add_edge(n, m, corridor);
                     }
-                    _ => None,
                 }
+            }
+        }
+
+        Junctions {
+            graph,
+            start: index[&self.start()],
+            goal: index[&self.goal()],
+        }
+    }
+
+    /// Draws the maze with the tiles of the `route` as `O`.
+    pub fn overlay(&self, route: &Route) -> String {
+        let cells = route.cells.iter().collect::<HashSet<_>>();
+
+        self.to_string()
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        if cells.contains(&IVec2::new(x as i32, y as i32)) {
+                            'O'
+                        } else {
+                            c
+                        }
+                    })
+                    .collect::<String>()
+                    + "\n"
             })
             .collect()
     }
 }
 
-pub fn solve(input: &'static str) -> String {
-    let env: Env = input.parse().unwrap();
+/// Longest simple path over the junction graph as a depth first search on a bitmask of visited
+/// junctions, pruned by the longest corridor still able to lead into each unvisited junction.
+struct Search {
+    // adjacents[n] = (m, length, edge)
+    adjacents: Vec<Vec<(usize, usize, EdgeIndex)>>,
+    longest_into: Vec<usize>,
+    goal: usize,
+    // the only junction leading to the goal, once there the goal must be next
+    last: Option<usize>,
+}
 
-    let s = IVec2::new(1, 0);
-    let g = IVec2::new(env.size.x - 2, env.size.y - 1);
+impl Search {
+    fn new(junctions: &Junctions) -> Self {
+        let graph = &junctions.graph;
+
+        assert!(graph.node_count() <= 64, "too many junctions");
+
+        let adjacents = graph
+            .node_indices()
+            .map(|n| {
+                graph
+                    .edges(n)
+                    .map(|e| {
+                        let m = if e.source() == n {
+                            e.target()
+                        } else {
+                            e.source()
+                        };
+                        (m.index(), e.weight().length(), e.id())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
 
-    let mut queue = VecDeque::new();
-    queue.push_front(s);
+        let longest_into = adjacents
+            .iter()
+            .map(|edges| edges.iter().map(|(_, l, _)| *l).max().unwrap_or(0))
+            .collect();
 
-    let mut visited = HashSet::new();
+        let goal = junctions.goal.index();
 
-    let mut graph = DiGraph::<IVec2, usize>::new();
+        let last = match adjacents[goal].as_slice() {
+            [(m, _, _)] => Some(*m),
+            _ => None,
+        };
 
-    while let Some(p) = queue.pop_back() {
-        if !visited.contains(&p) {
-            for (q, d) in env.adjacents_fast_forwarded(&p) {
-                let n = graph
-                    .node_indices()
-                    .find(|i| graph[*i] == p)
-                    .unwrap_or_else(|| graph.add_node(p));
+        Search {
+            adjacents,
+            longest_into,
+            goal,
+            last,
+        }
+    }
 
-                let m = graph
-                    .node_indices()
-                    .find(|i| graph[*i] == q)
-                    .unwrap_or_else(|| graph.add_node(q));
+    fn dfs(
+        &self,
+        n: usize,
+        visited: u64,
+        length: usize,
+        remaining: usize,
+        path: &mut Vec<EdgeIndex>,
+        best: &mut (usize, Vec<EdgeIndex>),
+    ) {
+        if n == self.goal {
+            if length > best.0 {
+                *best = (length, path.clone());
+            }
+            return;
+        }
 
-                graph.add_edge(n, m, d);
+        if length + remaining <= best.0 {
+            return;
+        }
 
-                queue.push_back(q);
+        for &(m, l, e) in &self.adjacents[n] {
+            if visited & (1 << m) != 0 || (Some(n) == self.last && m != self.goal) {
+                continue;
             }
 
-            visited.insert(p);
+            path.push(e);
+            self.dfs(
+                m,
+                visited | (1 << m),
+                length + l,
+                remaining - self.longest_into[m],
+                path,
+                best,
+            );
+            path.pop();
         }
     }
 
-    let s = graph.node_indices().find(|i| graph[*i] == s).unwrap();
-    let g = graph.node_indices().find(|i| graph[*i] == g).unwrap();
+    /// Partial paths a few junctions deep, to search each of them on its own.
+    fn branches(&self, start: usize, count: usize) -> Vec<(usize, u64, usize, Vec<EdgeIndex>)> {
+        let mut branches = vec![(start, 1u64 << start, 0, vec![])];
 
-    let ways = algo::all_simple_paths::<Vec<_>, _>(&graph, s, g, 0, None).collect::<Vec<_>>();
+        while branches.len() < count {
+            let mut next = vec![];
 
-    (ways
-        .iter()
-        .map(|w| {
-            let mut dist = 0;
+            for (n, visited, length, path) in &branches {
+                if *n == self.goal {
+                    next.push((*n, *visited, *length, path.clone()));
+                    continue;
+                }
 
-            for i in 0..w.len() - 1 {
-                let n = w[i];
-                let m = w[i + 1];
+                for &(m, l, e) in &self.adjacents[*n] {
+                    if visited & (1 << m) == 0 {
+                        let mut path = path.clone();
+                        path.push(e);
+                        next.push((m, visited | (1 << m), length + l, path));
+                    }
+                }
+            }
 
-                dist += graph.edge_weight(graph.find_edge(n, m).unwrap()).unwrap();
+            if next.len() <= branches.len() {
+                return next;
             }
 
-            dist
-        })
-        .max()
-        .unwrap())
-    .to_string()
+            branches = next;
+        }
+
+        branches
+    }
+
+    /// Length and corridors of the longest route, nothing if the goal can not be reached.
+    fn longest(&self, start: usize) -> (usize, Vec<EdgeIndex>) {
+        let branches = self.branches(start, 32);
+
+        let search = |(n, visited, length, mut path): (usize, u64, usize, Vec<EdgeIndex>)| {
+            let remaining = (0..self.adjacents.len())
+                .filter(|m| visited & (1 << m) == 0)
+                .map(|m| self.longest_into[m])
+                .sum();
+
+            let mut best = (0, vec![]);
+            self.dfs(n, visited, length, remaining, &mut path, &mut best);
+            best
+        };
+
+        #[cfg(feature = "rayon")]
+        let results = branches.into_par_iter().map(search).collect::<Vec<_>>();
+
+        #[cfg(not(feature = "rayon"))]
+        let results = branches.into_iter().map(search).collect::<Vec<_>>();
+
+        results
+            .into_iter()
+            .max_by_key(|(length, _)| *length)
+            .unwrap_or_default()
+    }
+}
+
+pub fn longest_route(input: &str) -> Route {
+    let env: Env = input.parse().unwrap();
+
+    let junctions = env.junctions();
+    let graph = &junctions.graph;
+
+    let (length, edges) = Search::new(&junctions).longest(junctions.start.index());
+
+    let mut junctions_visited = vec![graph[junctions.start]];
+    let mut cells = vec![graph[junctions.start]];
+
+    for e in edges {
+        let corridor = &graph[e];
+        let at = *junctions_visited.last().unwrap();
+
+        if corridor.cells[0] == at {
+            cells.extend(corridor.cells.iter().skip(1));
+        } else {
+            cells.extend(corridor.cells.iter().rev().skip(1));
+        }
+
+        junctions_visited.push(*cells.last().unwrap());
+    }
+
+    Route {
+        length,
+        junctions: junctions_visited,
+        cells,
+    }
+}
+
+/// The maze with the longest route drawn over it.
+pub fn render(input: &str) -> String {
+    let env: Env = input.parse().unwrap();
+
+    env.overlay(&longest_route(input))
+}
+
+pub fn solve(input: &'static str) -> String {
+    longest_route(input).length.to_string()
 }
 
 #[cfg(test)]
@@ -218,7 +436,7 @@ mod tests {
     }
 
     #[test]
-    fn fast_forward_test() {
+    fn adjacents_test() {
         let input = r"#########
 #.......#
 #########";
@@ -233,7 +451,7 @@ mod tests {
     }
 
     #[test]
-    fn fast_forward_test_2() {
+    fn corridor_to_junction_test() {
         let input = r"#.#######
 #....####
 ####....#
@@ -244,13 +462,14 @@ mod tests {
 
         let p = IVec2::new(1, 0);
 
-        let adjacents = env.adjacents_fast_forwarded(&p);
+        let corridor = env.corridor(&p, &(p + IVec2::Y));
 
-        assert_eq!(adjacents, vec![(IVec2::new(4, 2), 5)]);
+        assert_eq!(corridor.cells.last(), Some(&IVec2::new(4, 2)));
+        assert_eq!(corridor.length(), 5);
     }
 
     #[test]
-    fn fast_forward_test_3() {
+    fn corridor_to_dead_end_test() {
         let input = r"#.#######
 #....####
 ####....#
@@ -260,8 +479,52 @@ mod tests {
 
         let p = IVec2::new(1, 0);
 
-        let adjacents = env.adjacents_fast_forwarded(&p);
+        let corridor = env.corridor(&p, &(p + IVec2::Y));
+
+        assert_eq!(corridor.cells.last(), Some(&IVec2::new(7, 3)));
+        assert_eq!(corridor.length(), 9);
+    }
+
+    #[test]
+    fn junctions_test() {
+        let env = SAMPLE.parse::<Env>().unwrap();
+
+        let junctions = env.junctions();
+
+        assert_eq!(junctions.graph.node_count(), 9);
+        assert_eq!(junctions.graph.edge_count(), 12);
+    }
+
+    #[test]
+    fn route_test() {
+        let route = longest_route(SAMPLE);
+
+        assert_eq!(route.length, 154);
+        assert_eq!(route.cells.len(), 155);
+        assert_eq!(route.cells.iter().collect::<HashSet<_>>().len(), 155);
+        assert_eq!(route.junctions.first(), Some(&IVec2::new(1, 0)));
+        assert_eq!(route.junctions.last(), Some(&IVec2::new(21, 22)));
+
+        for pair in route.cells.windows(2) {
+            let step = (pair[0] - pair[1]).abs();
+            assert_eq!(step.x + step.y, 1);
+        }
+
+        let rendered = render(SAMPLE);
+        assert_eq!(rendered.chars().filter(|c| *c == 'O').count(), 155);
+    }
+
+    #[test]
+    fn unreachable_goal_test() {
+        let input = r"#.###
+#.###
+#####
+###.#
+###.#";
+
+        let route = longest_route(input);
 
-        assert_eq!(adjacents, vec![(IVec2::new(7, 3), 9)]);
+        assert_eq!(route.length, 0);
+        assert_eq!(route.cells, [IVec2::new(1, 0)]);
     }
 }
@@ -5,9 +5,15 @@
  */
 
 use advent_of_code::part_1;
-use std::error::Error;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() {
     let input = include_str!("../../input.txt");
-    Ok(println!("{}", part_1::solve(input)))
+
+    match part_1::solve(input) {
+        Ok(result) => println!("{result}"),
+        Err(diagnostics) => {
+            eprint!("{diagnostics}");
+            std::process::exit(1);
+        }
+    }
 }
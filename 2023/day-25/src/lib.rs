@@ -4,5 +4,6 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
+pub mod min_cut;
 pub mod part_1;
 pub mod part_2;
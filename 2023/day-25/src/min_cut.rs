@@ -0,0 +1,593 @@
+/*
+ * Advent of code solutions
+ * https://www.github.com/scristobal/advent-of-code
+ * Licensed under MIT, 2023 Samuel Cristobal
+ */
+
+use aoc_graphs::NamedUnGraph;
+use std::{
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::Display,
+    str::FromStr,
+};
+
+/// Undirected weighted graph whose nodes keep the component names of the input.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    names: Vec<String>,
+    adjacents: Vec<HashMap<usize, u32>>,
+}
+
+/// A cut splitting the graph in two, `side` are the nodes on one of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub value: u32,
+    pub side: Vec<usize>,
+}
+
+/// A split of the graph in `parts`, and the total weight of the edges between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    pub value: u32,
+    pub parts: Vec<Vec<usize>>,
+}
+
+impl FromStr for Graph {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let named = s.parse::<NamedUnGraph>().map_err(|e| e.to_string())?;
+
+        let mut graph = Graph {
+            names: named.graph.node_weights().cloned().collect(),
+            adjacents: vec![HashMap::new(); named.graph.node_count()],
+        };
+
+        for edge in named.graph.raw_edges() {
+            graph.add_edge(edge.source().index(), edge.target().index(), 1);
+        }
+
+        Ok(graph)
+    }
+}
+
+impl Graph {
+    pub fn add_edge(&mut self, n: usize, m: usize, weight: u32) {
+        if n != m {
+            *self.adjacents[n].entry(m).or_default() += weight;
+            *self.adjacents[m].entry(n).or_default() += weight;
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, n: usize) -> &str {
+        &self.names[n]
+    }
+
+    /// Every edge once, as `(n, m, weight)` with `n < m`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+        self.adjacents
+            .iter()
+            .enumerate()
+            .flat_map(|(n, adjacents)| {
+                adjacents
+                    .iter()
+                    .filter(move |(&m, _)| n < m)
+                    .map(move |(&m, &w)| (n, m, w))
+            })
+    }
+
+    /// Edges with an end on each side of the cut, by the names of their components.
+    pub fn cut_edges(&self, side: &[usize]) -> Vec<(&str, &str)> {
+        let mut inside = vec![false; self.node_count()];
+
+        for &n in side {
+            inside[n] = true;
+        }
+
+        let mut edges = self
+            .edges()
+            .filter(|(n, m, _)| inside[*n] != inside[*m])
+            .map(|(n, m, _)| {
+                let (a, b) = (self.name(n), self.name(m));
+                if a < b {
+                    (a, b)
+                } else {
+                    (b, a)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        edges.sort();
+        edges
+    }
+
+    /// The graph induced by `nodes`, node `i` of it is `nodes[i]` of this one.
+    fn subgraph(&self, nodes: &[usize]) -> Graph {
+        let index = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| (n, i))
+            .collect::<HashMap<_, _>>();
+
+        let adjacents = nodes
+            .iter()
+            .map(|&n| {
+                self.adjacents[n]
+                    .iter()
+                    .filter_map(|(m, &w)| index.get(m).map(|&j| (j, w)))
+                    .collect()
+            })
+            .collect();
+
+        Graph {
+            names: nodes.iter().map(|&n| self.names[n].clone()).collect(),
+            adjacents,
+        }
+    }
+
+    /// Global minimum cut, merging the last two nodes of a maximum adjacency ordering each phase.
+    pub fn stoer_wagner(&self) -> Cut {
+        assert!(self.node_count() > 1, "a cut needs at least two nodes");
+
+        let mut adjacents = self.adjacents.clone();
+        let mut members = (0..self.node_count()).map(|n| vec![n]).collect::<Vec<_>>();
+        let mut active = vec![true; self.node_count()];
+
+        let mut best = Cut {
+            value: u32::MAX,
+            side: vec![],
+        };
+
+        for phase in 1..self.node_count() {
+            let mut weights = vec![0; self.node_count()];
+            let mut added = vec![false; self.node_count()];
+
+            let mut heap = (0..self.node_count())
+                .filter(|&n| active[n])
+                .map(|n| (0, n))
+                .collect::<BinaryHeap<_>>();
+
+            let (mut s, mut t) = (usize::MAX, usize::MAX);
+
+            while let Some((w, n)) = heap.pop() {
+                if added[n] || w != weights[n] {
+                    continue;
+                }
+
+                added[n] = true;
+                (s, t) = (t, n);
+
+                for (&m, &c) in &adjacents[n] {
+                    if !added[m] {
+                        weights[m] += c;
+                        heap.push((weights[m], m));
+                    }
+                }
+            }
+
+            if weights[t] < best.value {
+                best = Cut {
+                    value: weights[t],
+                    side: members[t].clone(),
+                };
+            }
+
+            if phase == self.node_count() - 1 {
+                break;
+            }
+
+            let merged = std::mem::take(&mut adjacents[t]);
+
+            for (m, c) in merged {
+                adjacents[m].remove(&t);
+
+                if m != s {
+                    *adjacents[s].entry(m).or_default() += c;
+                    *adjacents[m].entry(s).or_default() += c;
+                }
+            }
+
+            let moved = std::mem::take(&mut members[t]);
+            members[s].extend(moved);
+            active[t] = false;
+        }
+
+        best.side.sort();
+        best
+    }
+
+    /// Randomized recursive contraction, keeping the best cut out of `trials` runs.
+    pub fn karger_stein(&self, trials: usize, seed: u64) -> Cut {
+        assert!(self.node_count() > 1, "a cut needs at least two nodes");
+
+        let mut rng = SplitMix64(seed);
+
+        // weighted edges as parallel unit edges
+        let edges = self
+            .edges()
+            .flat_map(|(n, m, w)| (0..w).map(move |_| (n, m)))
+            .collect::<Vec<_>>();
+
+        let mut best = Cut {
+            value: u32::MAX,
+            side: vec![],
+        };
+
+        for _ in 0..trials.max(1) {
+            let (value, labels) = recursive_contraction(self.node_count(), &edges, &mut rng);
+
+            if (value as u32) < best.value {
+                best = Cut {
+                    value: value as u32,
+                    side: (0..self.node_count()).filter(|&n| labels[n]).collect(),
+                };
+            }
+        }
+
+        best
+    }
+
+    /// Minimum cut separating `s` from `t`, from a maximum flow found with shortest augmenting paths.
+    pub fn st_cut(&self, s: usize, t: usize) -> Cut {
+        assert_ne!(s, t, "source and sink must differ");
+
+        let mut residual = self.adjacents.clone();
+        let mut value = 0;
+
+        loop {
+            let mut parents = vec![usize::MAX; self.node_count()];
+            parents[s] = s;
+
+            let mut queue = VecDeque::from([s]);
+
+            while let Some(n) = queue.pop_front() {
+                if n == t {
+                    break;
+                }
+
+                for (&m, &c) in &residual[n] {
+                    if c > 0 && parents[m] == usize::MAX {
+                        parents[m] = n;
+                        queue.push_back(m);
+                    }
+                }
+            }
+
+            if parents[t] == usize::MAX {
+                let side = (0..self.node_count())
+                    .filter(|&n| parents[n] != usize::MAX)
+                    .collect();
+
+                return Cut { value, side };
+            }
+
+            let mut bottleneck = u32::MAX;
+            let mut m = t;
+
+            while m != s {
+                let n = parents[m];
+                bottleneck = bottleneck.min(residual[n][&m]);
+                m = n;
+            }
+
+            let mut m = t;
+
+            while m != s {
+                let n = parents[m];
+                *residual[n].get_mut(&m).unwrap() -= bottleneck;
+                *residual[m].entry(n).or_default() += bottleneck;
+                m = n;
+            }
+
+            value += bottleneck;
+        }
+    }
+
+    /// Global minimum cut as the cheapest `st_cut` from the first node to any other.
+    pub fn max_flow_min_cut(&self) -> Cut {
+        assert!(self.node_count() > 1, "a cut needs at least two nodes");
+
+        (1..self.node_count())
+            .map(|t| self.st_cut(0, t))
+            .min_by_key(|cut| cut.value)
+            .unwrap()
+    }
+
+    /// Splits in `k` parts by repeatedly cutting the part with the cheapest minimum cut.
+    pub fn partition(&self, k: usize) -> Partition {
+        assert!(
+            0 < k && k <= self.node_count(),
+            "can not split {} nodes in {} parts",
+            self.node_count(),
+            k
+        );
+
+        let mut parts = vec![(0..self.node_count()).collect::<Vec<_>>()];
+        let mut value = 0;
+
+        while parts.len() < k {
+            let (i, cut) = parts
+                .iter()
+                .enumerate()
+                .filter(|(_, part)| part.len() > 1)
+                .map(|(i, part)| (i, self.subgraph(part).stoer_wagner()))
+                .min_by_key(|(_, cut)| cut.value)
+                .unwrap();
+
+            let part = parts.swap_remove(i);
+
+            let (side, rest) = part
+                .iter()
+                .enumerate()
+                .partition::<Vec<_>, _>(|(j, _)| cut.side.contains(j));
+
+            parts.push(side.into_iter().map(|(_, &n)| n).collect());
+            parts.push(rest.into_iter().map(|(_, &n)| n).collect());
+
+            value += cut.value;
+        }
+
+        parts.sort();
+
+        Partition { value, parts }
+    }
+}
+
+/// Contracts random edges until only `target` nodes are left,
+/// returns the new label of every node and the edges still crossing between them.
+fn contract(
+    nodes: usize,
+    edges: &[(usize, usize)],
+    target: usize,
+    rng: &mut SplitMix64,
+) -> (Vec<usize>, Vec<(usize, usize)>) {
+    let mut order = edges.to_vec();
+    rng.shuffle(&mut order);
+
+    let mut parents = (0..nodes).collect::<Vec<_>>();
+
+    fn find(parents: &mut [usize], n: usize) -> usize {
+        let mut n = n;
+        while parents[n] != n {
+            parents[n] = parents[parents[n]];
+            n = parents[n];
+        }
+        n
+    }
+
+    let mut count = nodes;
+
+    for (n, m) in order {
+        if count <= target {
+            break;
+        }
+
+        let (a, b) = (find(&mut parents, n), find(&mut parents, m));
+
+        if a != b {
+            parents[a] = b;
+            count -= 1;
+        }
+    }
+
+    let mut labels = HashMap::new();
+
+    let relabel = (0..nodes)
+        .map(|n| {
+            let root = find(&mut parents, n);
+            let next = labels.len();
+            *labels.entry(root).or_insert(next)
+        })
+        .collect::<Vec<_>>();
+
+    let crossing = edges
+        .iter()
+        .map(|&(n, m)| (relabel[n], relabel[m]))
+        .filter(|(n, m)| n != m)
+        .collect();
+
+    (relabel, crossing)
+}
+
+/// Karger–Stein: contract to about `n / √2` nodes twice and recurse on both, keeping the best.
+fn recursive_contraction(
+    nodes: usize,
+    edges: &[(usize, usize)],
+    rng: &mut SplitMix64,
+) -> (usize, Vec<bool>) {
+    if nodes <= 6 {
+        return (0..nodes * nodes)
+            .map(|_| {
+                let (labels, crossing) = contract(nodes, edges, 2, rng);
+                (crossing.len(), labels.iter().map(|&l| l == 0).collect())
+            })
+            .min_by_key(|(value, _)| *value)
+            .unwrap();
+    }
+
+    let target = (1.0 + nodes as f64 / std::f64::consts::SQRT_2).ceil() as usize;
+
+    (0..2)
+        .map(|_| {
+            let (labels, crossing) = contract(nodes, edges, target, rng);
+
+            // no edge left between the groups, the graph is not connected
+            if crossing.is_empty() {
+                return (0, labels.iter().map(|&l| l == 0).collect());
+            }
+
+            let groups = labels.iter().max().unwrap() + 1;
+            let (value, sides) = recursive_contraction(groups, &crossing, rng);
+            (value, labels.iter().map(|&l| sides[l]).collect())
+        })
+        .min_by_key(|(value, _)| *value)
+        .unwrap()
+}
+
+/// Small deterministic random number generator, enough to shuffle edges.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Why a cut does not split the graph the way it was expected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub expected: u32,
+    pub value: u32,
+    pub sizes: (usize, usize),
+    pub edges: Vec<(String, String)>,
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "minimum cut is {}, expected {}, splitting {} and {} components through:",
+            self.value, self.expected, self.sizes.0, self.sizes.1
+        )?;
+
+        for (a, b) in &self.edges {
+            writeln!(f, "  {a}/{b}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+impl Graph {
+    /// Sizes of the two groups left by the minimum cut, if it has exactly `expected` edges.
+    pub fn split(&self, expected: u32) -> Result<(usize, usize), Diagnostics> {
+        let cut = self.stoer_wagner();
+
+        let sizes = (cut.side.len(), self.node_count() - cut.side.len());
+
+        if cut.value == expected {
+            return Ok(sizes);
+        }
+
+        Err(Diagnostics {
+            expected,
+            value: cut.value,
+            sizes,
+            edges: self
+                .cut_edges(&cut.side)
+                .into_iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../sample.txt");
+
+    const EXPECTED_EDGES: [(&str, &str); 3] = [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")];
+
+    fn sizes(graph: &Graph, cut: &Cut) -> Vec<usize> {
+        let mut sizes = vec![cut.side.len(), graph.node_count() - cut.side.len()];
+        sizes.sort();
+        sizes
+    }
+
+    #[test]
+    fn stoer_wagner_test() {
+        let graph: Graph = SAMPLE.parse().unwrap();
+
+        let cut = graph.stoer_wagner();
+
+        assert_eq!(cut.value, 3);
+        assert_eq!(sizes(&graph, &cut), vec![6, 9]);
+        assert_eq!(graph.cut_edges(&cut.side), EXPECTED_EDGES);
+    }
+
+    #[test]
+    fn karger_stein_test() {
+        let graph: Graph = SAMPLE.parse().unwrap();
+
+        let cut = graph.karger_stein(10, 2023);
+
+        assert_eq!(cut.value, 3);
+        assert_eq!(graph.cut_edges(&cut.side), EXPECTED_EDGES);
+    }
+
+    #[test]
+    fn disconnected_test() {
+        let graph: Graph = "a: b\nc: d\ne: f\ng: h\ni: j\nk: l\nm: n".parse().unwrap();
+
+        let cut = graph.karger_stein(1, 1);
+
+        assert_eq!(cut.value, 0);
+        assert!(!cut.side.is_empty() && cut.side.len() < graph.node_count());
+        assert!(graph.cut_edges(&cut.side).is_empty());
+    }
+
+    #[test]
+    fn max_flow_test() {
+        let graph: Graph = SAMPLE.parse().unwrap();
+
+        let cut = graph.max_flow_min_cut();
+
+        assert_eq!(cut.value, 3);
+        assert_eq!(sizes(&graph, &cut), vec![6, 9]);
+        assert_eq!(graph.cut_edges(&cut.side), EXPECTED_EDGES);
+    }
+
+    #[test]
+    fn partition_test() {
+        let graph: Graph = "a: b c\nb: c\nc: d\nd: e f\ne: f\nf: g\ng: h i\nh: i"
+            .parse()
+            .unwrap();
+
+        let partition = graph.partition(3);
+
+        let names = partition
+            .parts
+            .iter()
+            .map(|part| part.iter().map(|&n| graph.name(n)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(partition.value, 2);
+        assert_eq!(
+            names,
+            vec![
+                vec!["a", "b", "c"],
+                vec!["d", "e", "f"],
+                vec!["g", "h", "i"]
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_test() {
+        let graph: Graph = "a: b c\nb: c\nc: d\nd: e f\ne: f".parse().unwrap();
+
+        let diagnostics = graph.split(3).unwrap_err();
+
+        assert_eq!(diagnostics.value, 1);
+        assert_eq!(diagnostics.edges, vec![("c".to_string(), "d".to_string())]);
+    }
+}
@@ -4,60 +4,14 @@
  * Licensed under MIT, 2023 Samuel Cristobal
  */
 
-use rustworkx_core::connectivity::stoer_wagner_min_cut;
+use crate::min_cut::{Diagnostics, Graph};
 
-use std::collections::HashSet;
-use std::collections::VecDeque;
+pub fn solve(input: &'static str) -> Result<String, Diagnostics> {
+    let graph: Graph = input.parse().unwrap();
 
-use petgraph::prelude::*;
+    let (a, b) = graph.split(3)?;
 
-fn parse(input: &str) -> Vec<(u32, u32)> {
-    let mut lexicon = Vec::new();
-
-    let mut set = HashSet::new();
-    input.lines().for_each(|l| {
-        let (n, d) = l.split_once(':').unwrap();
-
-        let n = match lexicon.iter().position(|&e| e == n) {
-            Some(l) => l,
-            None => {
-                lexicon.push(n);
-                lexicon.len() - 1
-            }
-        };
-
-        let d = d
-            .trim()
-            .split(' ')
-            .map(|n| match lexicon.iter().position(|&e| e == n) {
-                Some(l) => l,
-                None => {
-                    lexicon.push(n);
-                    lexicon.len() - 1
-                }
-            })
-            .collect::<VecDeque<_>>();
-
-        for m in d {
-            set.insert((n as u32, m as u32));
-        }
-    });
-
-    set.into_iter().collect()
-}
-
-pub fn solve(input: &'static str) -> String {
-    let edges = parse(input);
-
-    let graph: UnGraph<u32, ()> = UnGraph::from_edges(edges);
-
-    let (min_cut, partition) = stoer_wagner_min_cut(&graph, |_| Ok::<u32, ()>(1_u32))
-        .unwrap()
-        .unwrap();
-
-    assert_eq!(min_cut, 3);
-
-    ((graph.node_count() - partition.len()) * partition.len()).to_string()
+    Ok((a * b).to_string())
 }
 
 #[cfg(test)]
@@ -68,7 +22,16 @@ mod tests {
 
     #[test]
     fn solve_sample() {
-        let result = solve(SAMPLE);
+        let result = solve(SAMPLE).unwrap();
         assert_eq!(result, "54");
     }
+
+    #[test]
+    fn solve_diagnostics() {
+        let diagnostics = solve("a: b\nb: c").unwrap_err();
+        assert_eq!((diagnostics.value, diagnostics.expected), (1, 3));
+        assert!(diagnostics
+            .to_string()
+            .starts_with("minimum cut is 1, expected 3"));
+    }
 }
@@ -0,0 +1,12 @@
+use advent_of_code::part_2;
+use anyhow::Result;
+use aoc_render::terminal::Player;
+
+/// `play [frames per second]`, watch the guard walk in the terminal.
+fn main() -> Result<()> {
+    let input = include_str!("../../input.txt");
+
+    let fps = std::env::args().nth(1).map_or(Ok(30.0), |n| n.parse())?;
+
+    Player::new(fps).play(part_2::frames(input))
+}
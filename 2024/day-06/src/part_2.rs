@@ -1,5 +1,8 @@
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
 
 enum Tile {
     Empty,
@@ -80,7 +83,7 @@ fn is_loop(mut player: Player, board: &HashMap<(i32, i32), Tile>) -> bool {
     }
 }
 
-pub fn solve(input: &'static str) -> Result<String> {
+fn parse(input: &str) -> (HashMap<(i32, i32), Tile>, (i32, i32)) {
     let mut board = HashMap::<(i32, i32), Tile>::new();
 
     let mut initial_position = (0, 0);
@@ -104,6 +107,75 @@ pub fn solve(input: &'static str) -> Result<String> {
         }
     }
 
+    (board, initial_position)
+}
+
+/// The guard walk so far, blocks as `#`, visited tiles as `X` and the guard as an arrow.
+struct Walk {
+    board: HashMap<(i32, i32), Tile>,
+    player: Player,
+    visited: HashSet<(i32, i32)>,
+}
+
+impl Display for Walk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.board.keys().map(|p| p.0).max().unwrap_or(-1) + 1;
+        let height = self.board.keys().map(|p| p.1).max().unwrap_or(-1) + 1;
+
+        for y in 0..height {
+            for x in 0..width {
+                let char = if self.player.position == (x, y) {
+                    match self.player.direction {
+                        Direction::Up => '^',
+                        Direction::Right => '>',
+                        Direction::Down => 'v',
+                        Direction::Left => '<',
+                    }
+                } else if let Some(Tile::Block) = self.board.get(&(x, y)) {
+                    '#'
+                } else if self.visited.contains(&(x, y)) {
+                    'X'
+                } else {
+                    '.'
+                };
+
+                write!(f, "{char}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The map after every step of the guard, until it walks off.
+pub fn frames(input: &str) -> impl Iterator<Item = String> {
+    let (board, initial_position) = parse(input);
+
+    let mut walk = Walk {
+        board,
+        player: Player {
+            position: initial_position,
+            direction: Direction::Up,
+        },
+        visited: HashSet::from([initial_position]),
+    };
+
+    let first = walk.to_string();
+
+    std::iter::once(first).chain(std::iter::from_fn(move || {
+        let tile = walk.board.get(&walk.player.next_position())?;
+
+        walk.player.update(tile);
+        walk.visited.insert(walk.player.position);
+
+        Some(walk.to_string())
+    }))
+}
+
+pub fn solve(input: &'static str) -> Result<String> {
+    let (mut board, initial_position) = parse(input);
+
     let mut player = Player {
         position: initial_position,
         direction: Direction::Up,
@@ -153,4 +225,15 @@ mod tests {
         let result = solve(SAMPLE).unwrap();
         assert_eq!(result, "6");
     }
+
+    #[test]
+    fn frames_sample() {
+        let frames = frames(SAMPLE).collect::<Vec<_>>();
+
+        let last = frames.last().unwrap();
+
+        // the guard stands on the last visited tile, about to walk off
+        assert_eq!(last.matches('X').count() + 1, 41);
+        assert!(last.lines().last().unwrap().contains('v'));
+    }
 }
@@ -0,0 +1,26 @@
+use advent_of_code::part_2::{self, Frame};
+use anyhow::Result;
+use aoc_render::Animation;
+
+/// Finds the picture and saves it next to the input as `picture.pbm` and `picture.png`,
+/// along with `picture.gif` of the robots gathering over the seconds before.
+fn main() -> Result<()> {
+    let input = include_str!("../../input.txt");
+
+    let frame = part_2::picture(input, 101, 103)?;
+
+    println!("After {} seconds:\n{frame}", frame.secs);
+
+    std::fs::write("picture.pbm", frame.pbm())?;
+    frame.write_png(std::fs::File::create("picture.png")?, 4)?;
+
+    let mut animation = Animation::new(Frame::palette(), 4).delay(20);
+
+    for secs in (frame.secs - 30).max(0)..=frame.secs {
+        animation.push(&part_2::frame(input, 101, 103, secs)?.to_string());
+    }
+
+    animation.save_gif("picture.gif")?;
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use anyhow::Result;
-use itertools::Itertools;
+use anyhow::{bail, Result};
+use aoc_render::{Palette, BLACK, WHITE};
 use scanf::sscanf;
+use std::io::Write;
 
 struct Map {
     width: i32,
@@ -14,25 +15,25 @@ struct Robot {
 }
 
 impl Robot {
-    fn update(&mut self, map: &Map) {
-        self.p = (
-            (self.p.0 + self.v.0).rem_euclid(map.width),
-            (self.p.1 + self.v.1).rem_euclid(map.height),
+    fn at(&self, secs: i64, map: &Map) -> (i32, i32) {
+        (
+            (self.p.0 as i64 + self.v.0 as i64 * secs).rem_euclid(map.width as i64) as i32,
+            (self.p.1 as i64 + self.v.1 as i64 * secs).rem_euclid(map.height as i64) as i32,
         )
     }
 }
 
-fn solve_size(input: &'static str, width: i32, height: i32) -> Result<String> {
+fn parse(input: &str) -> Result<Vec<Robot>> {
     let mut robots = Vec::<Robot>::new();
 
-    for line in input.lines() {
+    for line in input.lines().filter(|line| !line.is_empty()) {
         let mut x = 0_i32;
         let mut y = 0_i32;
 
         let mut u = 0_i32;
         let mut v = 0_i32;
 
-        sscanf!(line, "p={},{} v={},{}", x, y, u, v).unwrap();
+        sscanf!(line, "p={},{} v={},{}", x, y, u, v)?;
 
         robots.push(Robot {
             p: (x, y),
@@ -40,37 +41,217 @@ fn solve_size(input: &'static str, width: i32, height: i32) -> Result<String> {
         });
     }
 
-    let map = Map { width, height };
+    Ok(robots)
+}
 
-    let mut i = 0;
-    let secs = loop {
-        robots.iter_mut().for_each(|robot| robot.update(&map));
+fn variance(values: &[i32]) -> f64 {
+    let n = values.len() as f64;
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / n;
 
-        i += 1;
-        if robots.iter().map(|robot| robot.p).all_unique() {
-            break i;
-        }
-    };
+    values
+        .iter()
+        .map(|&v| (v as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n
+}
 
-    for y in 0..height {
-        for x in 0..width {
-            let r = robots
+/// Second within a `period` where the coordinate picked by `axis` is the most clustered.
+///
+/// Each coordinate repeats with the size of the map along its axis, so the picture shows up
+/// when both axes hit their minimum variance at the same time.
+fn best_phase(robots: &[Robot], map: &Map, period: i32, axis: fn((i32, i32)) -> i32) -> i64 {
+    (0..period as i64)
+        .map(|secs| {
+            let values = robots
                 .iter()
-                .filter(|robot| robot.p.0 == x && robot.p.1 == y)
-                .count();
+                .map(|robot| axis(robot.at(secs, map)))
+                .collect::<Vec<_>>();
+
+            (secs, variance(&values))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(secs, _)| secs)
+}
+
+/// `(gcd, x, y)` such that `a * x + b * y = gcd`.
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+
+    let (gcd, x, y) = extended_gcd(b, a.rem_euclid(b));
 
-            if r == 0 {
-                print!(".");
-            } else {
-                print!("{r}")
+    (gcd, y, x - a.div_euclid(b) * y)
+}
+
+/// Smallest `t` with `t = a mod n` and `t = b mod m`, for coprime `n` and `m`.
+fn crt(a: i64, n: i64, b: i64, m: i64) -> Option<i64> {
+    let (gcd, x, _) = extended_gcd(n, m);
+
+    if gcd != 1 {
+        return None;
+    }
+
+    // t = a + n * k, with n * k = b - a mod m and x the inverse of n mod m
+    let k = ((b - a) * x).rem_euclid(m);
+
+    Some(a + n * k)
+}
+
+/// Robots on the map at some second, `true` where there is at least one.
+pub struct Frame {
+    pub secs: i64,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pixels.chunks(self.width) {
+            for &pixel in row {
+                write!(f, "{}", if pixel { '#' } else { '.' })?;
             }
+            writeln!(f)?;
         }
-        println!();
+        Ok(())
+    }
+}
+
+impl Frame {
+    /// Plain PBM image, `1` is black.
+    pub fn pbm(&self) -> String {
+        let mut res = format!("P1\n{} {}\n", self.width, self.height);
+
+        for row in self.pixels.chunks(self.width) {
+            let row = row
+                .iter()
+                .map(|&pixel| if pixel { "1" } else { "0" })
+                .collect::<Vec<_>>();
+
+            res.push_str(&row.join(" "));
+            res.push('\n');
+        }
+
+        res
     }
 
-    Ok(secs.to_string())
+    /// Robots in black over white.
+    pub fn palette() -> Palette {
+        Palette::new(WHITE).tile('#', BLACK)
+    }
+
+    /// PNG image, `scale` pixels per tile.
+    pub fn write_png(&self, w: impl Write, scale: usize) -> Result<()> {
+        let palette = Self::palette();
+
+        aoc_render::Frame::from_text(&self.to_string(), &palette).write_png(w, &palette, scale)
+    }
+}
+
+/// Robots on the map after `secs` seconds.
+pub fn frame(input: &str, width: i32, height: i32, secs: i64) -> Result<Frame> {
+    let robots = parse(input)?;
+
+    let map = Map { width, height };
+
+    let mut pixels = vec![false; (width * height) as usize];
+
+    for robot in &robots {
+        let (x, y) = robot.at(secs, &map);
+        pixels[(y * width + x) as usize] = true;
+    }
+
+    Ok(Frame {
+        secs,
+        width: width as usize,
+        height: height as usize,
+        pixels,
+    })
+}
+
+/// The frame where the robots cluster along both axes, found through the best phase of each
+/// axis instead of simulating every second.
+pub fn picture(input: &str, width: i32, height: i32) -> Result<Frame> {
+    let robots = parse(input)?;
+
+    if robots.is_empty() {
+        bail!("no robots in the input");
+    }
+
+    let map = Map { width, height };
+
+    let x_phase = best_phase(&robots, &map, width, |p| p.0);
+    let y_phase = best_phase(&robots, &map, height, |p| p.1);
+
+    let Some(secs) = crt(x_phase, width as i64, y_phase, height as i64) else {
+        bail!("map sizes {width} and {height} are not coprime");
+    };
+
+    frame(input, width, height, secs)
+}
+
+fn solve_size(input: &str, width: i32, height: i32) -> Result<String> {
+    Ok(picture(input, width, height)?.secs.to_string())
 }
 
 pub fn solve(input: &'static str) -> Result<String> {
     solve_size(input, 101, 103)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Robots that gather in a square at `secs`, with speeds from -9 to 9 spread over them.
+    fn gathering(secs: i32, width: i32, height: i32) -> String {
+        let mut res = String::new();
+
+        for i in 0..60 {
+            let (x, y) = (width / 2 + i % 3, height / 2 + i / 3 % 3);
+            let (u, v) = (i * 7 % 19 - 9, i * 11 % 19 - 9);
+
+            let px = (x - u * secs).rem_euclid(width);
+            let py = (y - v * secs).rem_euclid(height);
+
+            res.push_str(&format!("p={px},{py} v={u},{v}\n"));
+        }
+
+        res
+    }
+
+    #[test]
+    fn crt_test() {
+        assert_eq!(crt(2, 3, 3, 5), Some(8));
+        assert_eq!(crt(5, 101, 7, 103), Some(10307));
+        assert_eq!(crt(1, 4, 1, 6), None);
+    }
+
+    #[test]
+    fn picture_test() {
+        for secs in [0, 40, 76] {
+            let input = gathering(secs, 11, 7);
+
+            let frame = picture(&input, 11, 7).unwrap();
+
+            assert_eq!(frame.secs, secs as i64);
+            assert_eq!(frame.pixels.iter().filter(|&&p| p).count(), 9);
+        }
+
+        let input = gathering(6000, 101, 103);
+        assert_eq!(solve_size(&input, 101, 103).unwrap(), "6000");
+    }
+
+    #[test]
+    fn export_test() {
+        let frame = picture(&gathering(30, 11, 7), 11, 7).unwrap();
+
+        let pbm = frame.pbm();
+        assert!(pbm.starts_with("P1\n11 7\n"));
+        assert_eq!(pbm.matches('1').count(), 9 + 3);
+
+        let mut png = vec![];
+        frame.write_png(&mut png, 2).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+}
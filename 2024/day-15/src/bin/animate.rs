@@ -0,0 +1,39 @@
+use advent_of_code::world::Replay;
+use anyhow::Result;
+use aoc_render::{save_png, Animation, Palette, BROWN, GRAY, RED, WHITE};
+
+/// `animate [scale] [keep one frame every] [replay file]`, the puzzle input when no replay is given.
+fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let scale = args.get(1).map_or(Ok(2), |n| n.parse())?;
+    let every = args.get(2).map_or(Ok(1), |n| n.parse())?;
+
+    let replay = match args.get(3) {
+        Some(path) => std::fs::read_to_string(path)?.parse()?,
+        None => Replay::from_input(include_str!("../../input.txt"), scale)?,
+    };
+
+    let palette = Palette::new(WHITE)
+        .tile('#', GRAY)
+        .tile('@', RED)
+        .tile('O', BROWN)
+        .tile('[', BROWN)
+        .tile('=', BROWN)
+        .tile(']', BROWN);
+
+    let mut animation = Animation::new(palette.clone(), 4).delay(5).every(every);
+
+    let mut player = replay.play()?;
+    animation.push(&player.world.to_string());
+
+    while player.step()?.is_some() {
+        animation.push(&player.world.to_string());
+    }
+
+    animation.save_gif("warehouse.gif")?;
+    save_png("warehouse.png", &player.world.to_string(), &palette, 4)?;
+
+    println!("{} frames saved to warehouse.gif", animation.len());
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use advent_of_code::planner::Puzzle;
+use anyhow::{bail, Context, Result};
+
+/// `plan <map file> [scale]`, targets marked with `x`, or `X` with a rock on it.
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    let path = args.next().context("usage: plan <map file> [scale]")?;
+    let scale = args.next().map_or(Ok(1), |s| s.parse())?;
+
+    let puzzle = Puzzle::new(std::fs::read_to_string(path)?.trim_end(), scale)?;
+
+    let Some(moves) = puzzle.plan() else {
+        bail!("no sequence of moves puts a rock on every target");
+    };
+
+    println!(
+        "{} moves: {}",
+        moves.len(),
+        moves.iter().collect::<String>()
+    );
+    Ok(())
+}
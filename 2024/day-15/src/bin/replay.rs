@@ -0,0 +1,44 @@
+use advent_of_code::world::{simulate, Replay};
+use anyhow::{bail, Result};
+use std::io::BufRead;
+
+/// `replay record <scale> <replay file>` runs the puzzle input and saves the moves,
+/// `replay play <replay file>` steps through them, one move per enter key.
+fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<_>>();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[1..] {
+        ["record", scale, path] => {
+            let input = include_str!("../../input.txt");
+
+            let world = simulate(input, scale.parse()?)?;
+
+            std::fs::write(path, world.record().to_string())?;
+
+            println!("{} moves recorded to {path}", world.record().moves.len());
+        }
+        ["play", path] => {
+            let replay: Replay = std::fs::read_to_string(path)?.parse()?;
+
+            let mut player = replay.play()?;
+
+            println!("Initial state:\n{}", player.world);
+
+            let mut lines = std::io::stdin().lock().lines();
+
+            while let Some((r#move, moved)) = player.step()? {
+                lines.next().transpose()?;
+
+                let (step, total) = player.steps();
+                let blocked = if moved { "" } else { " (blocked)" };
+
+                println!("Move {move} {step}/{total}{blocked}:\n{}", player.world);
+            }
+
+            println!("GPS: {}", player.world.gps());
+        }
+        _ => bail!("usage: replay record <scale> <file> | replay play <file>"),
+    }
+
+    Ok(())
+}
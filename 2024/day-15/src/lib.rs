@@ -1,3 +1,4 @@
-#![feature(let_chains)]
 pub mod part_1;
 pub mod part_2;
+pub mod planner;
+pub mod world;
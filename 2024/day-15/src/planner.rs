@@ -0,0 +1,233 @@
+use crate::world::{direction, World};
+use anyhow::{bail, Result};
+use std::collections::{HashMap, VecDeque};
+
+const MOVES: [char; 4] = ['^', 'v', '<', '>'];
+
+type Key = ((i32, i32), Vec<(i32, i32)>);
+
+/// A warehouse and the cells its rocks should end up at, the left tile for wide rocks.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub world: World,
+    pub targets: Vec<(i32, i32)>,
+}
+
+impl Puzzle {
+    /// Parses a map where `x` marks an empty target and `X` a rock already on its target.
+    pub fn new(map: &str, scale: usize) -> Result<Self> {
+        let mut targets = vec![];
+
+        for (y, line) in map.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                if char == 'x' || char == 'X' {
+                    targets.push(((scale * x) as i32, y as i32));
+                }
+            }
+        }
+
+        let world = World::new(&map.replace('x', ".").replace('X', "O"), scale)?;
+
+        if world.rocks.len() < targets.len() {
+            bail!(
+                "{} targets but only {} rocks",
+                targets.len(),
+                world.rocks.len()
+            );
+        }
+
+        Ok(Puzzle { world, targets })
+    }
+
+    fn key(world: &World) -> Key {
+        let mut rocks = world
+            .rocks
+            .iter()
+            .map(|rock| rock.coords)
+            .collect::<Vec<_>>();
+        rocks.sort();
+
+        (world.robot.coords, rocks)
+    }
+
+    pub fn is_solved(&self, world: &World) -> bool {
+        self.targets
+            .iter()
+            .all(|target| world.rocks.iter().any(|rock| rock.coords == *target))
+    }
+
+    /// Whether the rock, off target, can never be pushed again since walls block it both ways,
+    /// a wide rock can still be pushed down while the robot fits above any of its tiles.
+    pub fn is_deadlocked(&self, world: &World, rock: usize) -> bool {
+        let (x, y) = world.rocks[rock].coords;
+
+        if self.targets.contains(&(x, y)) {
+            return false;
+        }
+
+        let cells = (x..x + world.scale() as i32).collect::<Vec<_>>();
+
+        let horizontal =
+            !world.is_wall((x - 1, y)) && !world.is_wall((cells[cells.len() - 1] + 1, y));
+
+        let up = cells.iter().all(|&c| !world.is_wall((c, y - 1)))
+            && cells.iter().any(|&c| !world.is_wall((c, y + 1)));
+        let down = cells.iter().all(|&c| !world.is_wall((c, y + 1)))
+            && cells.iter().any(|&c| !world.is_wall((c, y - 1)));
+
+        !horizontal && !up && !down
+    }
+
+    /// Whether too many rocks are deadlocked for the rest to cover every target, spare rocks
+    /// can be stuck anywhere.
+    pub fn is_hopeless(&self, world: &World) -> bool {
+        let free = (0..world.rocks.len())
+            .filter(|&i| !self.is_deadlocked(world, i))
+            .count();
+
+        free < self.targets.len()
+    }
+
+    /// Shortest sequence of moves that leaves a rock on every target, breadth first over robot
+    /// moves, skipping hopeless states. `None` if there is none.
+    pub fn plan(&self) -> Option<Vec<char>> {
+        let start = Self::key(&self.world);
+
+        if self.is_hopeless(&self.world) {
+            return None;
+        }
+
+        let mut parents: HashMap<Key, Option<(Key, char)>> = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([self.world.clone()]);
+
+        while let Some(world) = queue.pop_front() {
+            if self.is_solved(&world) {
+                let mut moves = vec![];
+                let mut key = Self::key(&world);
+
+                while let Some(Some((parent, r#move))) = parents.get(&key) {
+                    moves.push(*r#move);
+                    key = parent.clone();
+                }
+
+                moves.reverse();
+                return Some(moves);
+            }
+
+            for r#move in MOVES {
+                let mut next = world.clone();
+
+                next.push(&direction(r#move).unwrap());
+
+                if !next.is_legal() {
+                    next.discard();
+                    continue;
+                }
+
+                let pushed = !next.pushed().is_empty();
+                next.apply();
+
+                if pushed && self.is_hopeless(&next) {
+                    continue;
+                }
+
+                let key = Self::key(&next);
+
+                if parents.contains_key(&key) {
+                    continue;
+                }
+
+                parents.insert(key, Some((Self::key(&world), r#move)));
+                queue.push_back(next);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(puzzle: &Puzzle, moves: &[char]) {
+        let mut world = puzzle.world.clone();
+
+        for &r#move in moves {
+            world.update(r#move).unwrap();
+        }
+
+        assert!(puzzle.is_solved(&world));
+    }
+
+    #[test]
+    fn plan_test() {
+        #[rustfmt::skip]
+        let puzzle = Puzzle::new(
+"######
+#@O.x#
+#....#
+######", 1).unwrap();
+
+        let moves = puzzle.plan().unwrap();
+
+        assert_eq!(moves, vec!['>', '>']);
+        check(&puzzle, &moves);
+    }
+
+    #[test]
+    fn wide_plan_test() {
+        #[rustfmt::skip]
+        let puzzle = Puzzle::new(
+"#######
+#.....#
+#.@O..#
+#.....#
+#...x.#
+#######", 2).unwrap();
+
+        let moves = puzzle.plan().unwrap();
+
+        check(&puzzle, &moves);
+        assert_eq!(moves.len(), 7);
+    }
+
+    #[test]
+    fn deadlock_test() {
+        #[rustfmt::skip]
+        let puzzle = Puzzle::new(
+"#####
+#O.x#
+#.@.#
+#####", 1).unwrap();
+
+        assert!(puzzle.is_deadlocked(&puzzle.world, 0));
+        assert_eq!(puzzle.plan(), None);
+
+        #[rustfmt::skip]
+        let puzzle = Puzzle::new(
+"#####
+#..O#
+#@..#
+#.x.#
+#####", 2).unwrap();
+
+        assert!(puzzle.is_deadlocked(&puzzle.world, 0));
+        assert_eq!(puzzle.plan(), None);
+    }
+
+    #[test]
+    fn spare_rock_test() {
+        // the rock in the corner is stuck, but it is not needed
+        #[rustfmt::skip]
+        let puzzle = Puzzle::new(
+"######
+#O...#
+#@O.x#
+######", 1).unwrap();
+
+        assert!(puzzle.is_deadlocked(&puzzle.world, 0));
+        assert!(!puzzle.is_hopeless(&puzzle.world));
+        assert_eq!(puzzle.plan(), Some(vec!['>', '>']));
+    }
+}
@@ -0,0 +1,451 @@
+use anyhow::{bail, Context, Result};
+use std::str::FromStr;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub coords: (i32, i32),
+    prove: (i32, i32),
+}
+
+impl Position {
+    fn new(coords: (i32, i32)) -> Self {
+        Position {
+            coords,
+            prove: coords,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Tile {
+    Empty,
+    Wall,
+    Rock(usize),
+}
+
+/// The warehouse with every tile of the input `scale` tiles wide, rocks as wide as a tile.
+#[derive(Debug, Clone)]
+pub struct World {
+    width: usize,
+    height: usize,
+    scale: usize,
+    tiles: Vec<Tile>,
+    pub rocks: Vec<Position>,
+    pub robot: Position,
+    pushed: Vec<usize>,
+    /// Whether each rock is in `pushed`, so a push stays linear in the rocks it moves.
+    proved: Vec<bool>,
+    map: String,
+    history: Vec<char>,
+}
+
+impl std::fmt::Display for World {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let pos = (x as i32, y as i32);
+
+                if self.robot.coords == pos {
+                    write!(f, "@")?;
+                } else {
+                    match self.tile(pos) {
+                        Tile::Wall => write!(f, "#")?,
+                        Tile::Empty => write!(f, ".")?,
+                        Tile::Rock(_) if self.scale == 1 => write!(f, "O")?,
+                        Tile::Rock(_) => {
+                            write!(f, "[{}]", "=".repeat(self.scale - 2))?;
+                            x += self.scale - 1;
+                        }
+                    }
+                }
+
+                x += 1;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn direction(r#move: char) -> Result<(i32, i32)> {
+    Ok(match r#move {
+        '<' => (-1, 0),
+        '>' => (1, 0),
+        '^' => (0, -1),
+        'v' => (0, 1),
+        _ => bail!("unknown move {move}"),
+    })
+}
+
+impl World {
+    pub fn new(map: &str, scale: usize) -> Result<Self> {
+        if scale == 0 {
+            bail!("scale must be at least 1");
+        }
+
+        let width = map.lines().next().unwrap_or_default().chars().count() * scale;
+        let height = map.lines().count();
+
+        let mut world = World {
+            width,
+            height,
+            scale,
+            tiles: vec![Tile::Empty; width * height],
+            rocks: Vec::new(),
+            robot: Position::default(),
+            pushed: Vec::new(),
+            proved: Vec::new(),
+            map: map.trim_end().to_string(),
+            history: Vec::new(),
+        };
+
+        for (y, line) in map.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                let coords = ((scale * x) as i32, y as i32);
+
+                match char {
+                    '#' => (0..scale as i32)
+                        .for_each(|i| world.set((coords.0 + i, coords.1), Tile::Wall)),
+                    'O' => {
+                        world.rocks.push(Position::new(coords));
+                        world.place(world.rocks.len() - 1);
+                    }
+                    '@' => world.robot = Position::new(coords),
+                    '.' => continue,
+                    _ => bail!("unknown tile {char} at {x},{y}"),
+                }
+            }
+        }
+
+        world.proved = vec![false; world.rocks.len()];
+
+        Ok(world)
+    }
+
+    fn index(&self, (x, y): (i32, i32)) -> Option<usize> {
+        (0 <= x && (x as usize) < self.width && 0 <= y && (y as usize) < self.height)
+            .then_some(y as usize * self.width + x as usize)
+    }
+
+    fn tile(&self, pos: (i32, i32)) -> Tile {
+        self.index(pos).map_or(Tile::Wall, |i| self.tiles[i])
+    }
+
+    fn set(&mut self, pos: (i32, i32), tile: Tile) {
+        if let Some(i) = self.index(pos) {
+            self.tiles[i] = tile;
+        }
+    }
+
+    fn cells(&self, (x, y): (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+        (0..self.scale as i32).map(move |i| (x + i, y))
+    }
+
+    fn place(&mut self, rock: usize) {
+        for cell in self.cells(self.rocks[rock].coords).collect::<Vec<_>>() {
+            self.set(cell, Tile::Rock(rock));
+        }
+    }
+
+    fn lift(&mut self, rock: usize) {
+        for cell in self.cells(self.rocks[rock].coords).collect::<Vec<_>>() {
+            self.set(cell, Tile::Empty);
+        }
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    pub fn is_wall(&self, pos: (i32, i32)) -> bool {
+        self.tile(pos) == Tile::Wall
+    }
+
+    /// Moves the robot if nothing it pushes hits a wall, returns whether it moved.
+    pub fn update(&mut self, r#move: char) -> Result<bool> {
+        let direction = direction(r#move)?;
+
+        self.history.push(r#move);
+
+        self.push(&direction);
+
+        Ok(match self.is_legal() {
+            true => {
+                self.apply();
+                true
+            }
+            false => {
+                self.discard();
+                false
+            }
+        })
+    }
+
+    /// Proves the robot and the chain of rocks it pushes one step in `direction`.
+    pub fn push(&mut self, direction: &(i32, i32)) {
+        self.robot.prove = (
+            self.robot.coords.0 + direction.0,
+            self.robot.coords.1 + direction.1,
+        );
+
+        let mut next_positions = vec![self.robot.prove];
+
+        while let Some(position) = next_positions.pop() {
+            let Tile::Rock(ind) = self.tile(position) else {
+                continue;
+            };
+
+            if self.proved[ind] {
+                continue;
+            }
+
+            let rock = &mut self.rocks[ind];
+            rock.prove = (rock.coords.0 + direction.0, rock.coords.1 + direction.1);
+
+            next_positions.extend(self.cells(self.rocks[ind].prove));
+            self.pushed.push(ind);
+            self.proved[ind] = true;
+        }
+    }
+
+    /// Rocks proved by the last `push`.
+    pub fn pushed(&self) -> &[usize] {
+        &self.pushed
+    }
+
+    pub fn is_legal(&self) -> bool {
+        !self.is_wall(self.robot.prove)
+            && self
+                .pushed
+                .iter()
+                .all(|&ind| self.cells(self.rocks[ind].prove).all(|c| !self.is_wall(c)))
+    }
+
+    pub fn discard(&mut self) {
+        self.robot.prove = self.robot.coords;
+
+        for ind in std::mem::take(&mut self.pushed) {
+            self.rocks[ind].prove = self.rocks[ind].coords;
+            self.proved[ind] = false;
+        }
+    }
+
+    pub fn apply(&mut self) {
+        self.robot.coords = self.robot.prove;
+
+        let pushed = std::mem::take(&mut self.pushed);
+
+        for &ind in &pushed {
+            self.lift(ind);
+        }
+
+        for &ind in &pushed {
+            self.rocks[ind].coords = self.rocks[ind].prove;
+            self.proved[ind] = false;
+            self.place(ind);
+        }
+    }
+
+    pub fn gps(&self) -> i32 {
+        self.rocks
+            .iter()
+            .map(|rock| rock.coords.0 + 100 * rock.coords.1)
+            .sum()
+    }
+
+    /// The moves so far as a replay file.
+    pub fn record(&self) -> Replay {
+        Replay {
+            scale: self.scale,
+            map: self.map.clone(),
+            moves: self.history.clone(),
+        }
+    }
+}
+
+/// Runs every move of the `input` on the map `scale` times wider.
+pub fn simulate(input: &str, scale: usize) -> Result<World> {
+    let replay = Replay::from_input(input, scale)?;
+
+    let mut player = replay.play()?;
+
+    while player.step()?.is_some() {}
+
+    Ok(player.world)
+}
+
+/// A map, how much wider it is and the moves made on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub scale: usize,
+    pub map: String,
+    pub moves: Vec<char>,
+}
+
+impl Replay {
+    pub fn from_input(input: &str, scale: usize) -> Result<Self> {
+        let input = input.replace("\r\n", "\n");
+        let (map, moves) = input.split_once("\n\n").context("missing moves")?;
+
+        let moves = moves
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| direction(c).map(|_| c))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Replay {
+            scale,
+            map: map.trim_end().to_string(),
+            moves,
+        })
+    }
+
+    pub fn play(&self) -> Result<Player> {
+        Ok(Player {
+            world: World::new(&self.map, self.scale)?,
+            moves: self.moves.clone(),
+            next: 0,
+        })
+    }
+}
+
+/// Replay file: the scale, the map and the moves, 70 per line, separated by blank lines.
+impl std::fmt::Display for Replay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "scale {}", self.scale)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.map)?;
+        writeln!(f)?;
+
+        for line in self.moves.chunks(70) {
+            writeln!(f, "{}", line.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.replace("\r\n", "\n");
+        let (header, input) = s.split_once("\n\n").context("missing replay header")?;
+
+        let scale = header
+            .strip_prefix("scale ")
+            .context("replay should start with `scale N`")?
+            .trim()
+            .parse()?;
+
+        Replay::from_input(input, scale)
+    }
+}
+
+/// Steps through the moves of a replay one at a time.
+pub struct Player {
+    pub world: World,
+    moves: Vec<char>,
+    next: usize,
+}
+
+impl Player {
+    /// Applies the next move, returns it and whether the robot moved, `None` once done.
+    pub fn step(&mut self) -> Result<Option<(char, bool)>> {
+        let Some(&r#move) = self.moves.get(self.next) else {
+            return Ok(None);
+        };
+
+        self.next += 1;
+
+        Ok(Some((r#move, self.world.update(r#move)?)))
+    }
+
+    pub fn steps(&self) -> (usize, usize) {
+        (self.next, self.moves.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+
+    #[test]
+    fn display_test() {
+        let world = World::new(SMALL.split_once("\n\n").unwrap().0, 2).unwrap();
+
+        assert_eq!(world.to_string().lines().nth(3).unwrap(), "##....[][]@.##");
+
+        let world = World::new(SMALL.split_once("\n\n").unwrap().0, 3).unwrap();
+
+        assert_eq!(
+            world.to_string().lines().nth(3).unwrap(),
+            "###......[=][=]@..###"
+        );
+    }
+
+    #[test]
+    fn wide_push_test() {
+        let world = simulate(SMALL, 2).unwrap();
+
+        #[rustfmt::skip]
+        assert_eq!(world.to_string(),
+"##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+");
+    }
+
+    #[test]
+    fn scales_test() {
+        for scale in 1..=4 {
+            let world = simulate(SMALL, scale).unwrap();
+
+            assert_eq!(world.rocks.len(), 3);
+
+            // every rock tile on the grid points back at its rock
+            for (ind, rock) in world.rocks.iter().enumerate() {
+                for cell in world.cells(rock.coords) {
+                    assert_eq!(world.tile(cell), Tile::Rock(ind));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn replay_test() {
+        let world = simulate(SMALL, 2).unwrap();
+
+        let file = world.record().to_string();
+        let replay: Replay = file.parse().unwrap();
+
+        assert_eq!(replay, world.record());
+
+        let mut player = replay.play().unwrap();
+        let mut frames = vec![player.world.to_string()];
+
+        while player.step().unwrap().is_some() {
+            frames.push(player.world.to_string());
+        }
+
+        assert_eq!(frames.len(), 12);
+        assert_eq!(frames.last().unwrap(), &world.to_string());
+        assert_eq!(player.steps(), (11, 11));
+    }
+}
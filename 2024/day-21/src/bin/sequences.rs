@@ -0,0 +1,37 @@
+use advent_of_code::keypad::{Chain, Keypad, ARROWPAD, NUMPAD};
+use anyhow::Result;
+use std::fs;
+
+// usage: sequences [layout files, from the door keypad to the human one]
+fn main() -> Result<()> {
+    let input = include_str!("../../input.txt");
+
+    let layouts = std::env::args()
+        .skip(1)
+        .map(fs::read_to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pads = if layouts.is_empty() {
+        [NUMPAD, ARROWPAD, ARROWPAD, ARROWPAD]
+            .iter()
+            .map(|layout| layout.parse())
+            .collect::<Result<Vec<Keypad>>>()?
+    } else {
+        layouts
+            .iter()
+            .map(|layout| layout.parse())
+            .collect::<Result<Vec<Keypad>>>()?
+    };
+
+    let chain = Chain::new(pads)?;
+
+    for code in input.lines().filter(|l| !l.is_empty()) {
+        println!("{code}: {} presses", chain.cost(code)?);
+
+        for (level, keys) in chain.sequences(code)?.iter().enumerate() {
+            println!("  {level}: {keys}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, bail, Result};
+use bimap::BiHashMap;
+use pathfinding::prelude::dijkstra;
+use std::{collections::HashMap, str::FromStr};
+
+pub const NUMPAD: &str = "789\n456\n123\n 0A";
+
+pub const ARROWPAD: &str = " ^A\n<v>";
+
+const DIRS: [(char, (i32, i32)); 4] =
+    [('^', (0, -1)), ('v', (0, 1)), ('>', (1, 0)), ('<', (-1, 0))];
+
+/// Keys by position, spaces in the layout are gaps the robot arm must never point at.
+#[derive(Debug, Clone)]
+pub struct Keypad {
+    keys: BiHashMap<char, (i32, i32)>,
+}
+
+impl FromStr for Keypad {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut keys = BiHashMap::new();
+
+        for (y, ln) in s.lines().enumerate() {
+            for (x, ch) in ln.chars().enumerate().filter(|&(_, ch)| ch != ' ') {
+                if keys.insert_no_overwrite(ch, (x as i32, y as i32)).is_err() {
+                    bail!("key {ch} appears twice in the layout");
+                }
+            }
+        }
+
+        if !keys.contains_left(&'A') {
+            bail!("layout has no A key for the arm to start at");
+        }
+
+        Ok(Keypad { keys })
+    }
+}
+
+impl Keypad {
+    fn coord(&self, key: char) -> Result<(i32, i32)> {
+        self.keys
+            .get_by_left(&key)
+            .copied()
+            .ok_or_else(|| anyhow!("no key {key} in the layout"))
+    }
+
+    fn is_directional(&self) -> bool {
+        ['^', 'v', '<', '>', 'A']
+            .iter()
+            .all(|k| self.keys.contains_left(k))
+    }
+}
+
+/// `(start, end)` to the human presses and the keys on the driving pad
+/// that move an arm from `start` to `end` and press it.
+type Moves = HashMap<(char, char), (u64, Vec<char>)>;
+
+#[derive(Eq, PartialEq, Clone, Hash)]
+struct ArmState {
+    coord: (i32, i32),
+    last_instr: char,
+    pressed: bool,
+}
+
+/// Keypads from the one typing the code to the one the human presses,
+/// each but the first operated by a robot arm driven from the next one.
+pub struct Chain {
+    pads: Vec<Keypad>,
+    // moves[i] for the arm on pads[i], driven from pads[i + 1]
+    moves: Vec<Moves>,
+}
+
+impl Chain {
+    pub fn new(pads: Vec<Keypad>) -> Result<Self> {
+        if pads.is_empty() {
+            bail!("a chain needs at least one keypad");
+        }
+
+        if let Some(i) = pads.iter().skip(1).position(|pad| !pad.is_directional()) {
+            bail!("keypad {} drives a robot arm but has no arrow keys", i + 1);
+        }
+
+        let mut moves = vec![Moves::new(); pads.len()];
+
+        // the human presses keys on the last pad directly
+        let last = pads.len() - 1;
+
+        for &start in pads[last].keys.left_values() {
+            for &end in pads[last].keys.left_values() {
+                moves[last].insert((start, end), (1, vec![]));
+            }
+        }
+
+        for i in (0..last).rev() {
+            for &start in pads[i].keys.left_values() {
+                for &end in pads[i].keys.left_values() {
+                    let found = Self::arm_moves(&pads[i], &pads[i + 1], &moves[i + 1], start, end)?;
+                    moves[i].insert((start, end), found);
+                }
+            }
+        }
+
+        Ok(Chain { pads, moves })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.pads.len()
+    }
+
+    /// Cheapest keys on `driver` to move the arm on `pad` from `start` to `end` and press it.
+    fn arm_moves(
+        pad: &Keypad,
+        driver: &Keypad,
+        costs: &Moves,
+        start: char,
+        end: char,
+    ) -> Result<(u64, Vec<char>)> {
+        let init = ArmState {
+            coord: pad.coord(start)?,
+            last_instr: 'A',
+            pressed: false,
+        };
+
+        let cost = |from: char, to: char| costs.get(&(from, to)).unwrap().0;
+
+        let successors = |state: &ArmState| {
+            let mut res = vec![];
+
+            if state.pressed {
+                return res;
+            }
+
+            for (ins, delta) in DIRS {
+                let coord_next = (state.coord.0 + delta.0, state.coord.1 + delta.1);
+
+                if pad.keys.contains_right(&coord_next) && driver.keys.contains_left(&ins) {
+                    res.push((
+                        ArmState {
+                            coord: coord_next,
+                            last_instr: ins,
+                            pressed: false,
+                        },
+                        cost(state.last_instr, ins),
+                    ));
+                }
+            }
+
+            if pad.keys.get_by_right(&state.coord) == Some(&end) {
+                res.push((
+                    ArmState {
+                        coord: state.coord,
+                        last_instr: 'A',
+                        pressed: true,
+                    },
+                    cost(state.last_instr, 'A'),
+                ));
+            }
+
+            res
+        };
+
+        let (path, cost) = dijkstra(&init, successors, |s| s.pressed)
+            .ok_or_else(|| anyhow!("the arm can not go from {start} to {end}"))?;
+
+        Ok((cost, path.iter().skip(1).map(|s| s.last_instr).collect()))
+    }
+
+    /// Presses on the human keypad to type `code` on the first one.
+    pub fn cost(&self, code: &str) -> Result<u64> {
+        let mut last = 'A';
+        let mut total = 0;
+
+        for key in code.chars() {
+            total += self.moves[0]
+                .get(&(last, key))
+                .ok_or_else(|| anyhow!("no key {key} in the first keypad"))?
+                .0;
+            last = key;
+        }
+
+        Ok(total)
+    }
+
+    /// Keys pressed on every keypad to type `code`, from the first keypad to the human one.
+    ///
+    /// Lengths grow exponentially with the depth of the chain, use `cost` for deep chains.
+    pub fn sequences(&self, code: &str) -> Result<Vec<String>> {
+        let mut levels = vec![code.chars().collect::<Vec<_>>()];
+
+        for i in 0..self.depth() - 1 {
+            let mut last = 'A';
+            let mut next = vec![];
+
+            for &key in levels.last().unwrap() {
+                let (_, keys) = self.moves[i]
+                    .get(&(last, key))
+                    .ok_or_else(|| anyhow!("no key {key} in keypad {i}"))?;
+                next.extend(keys);
+                last = key;
+            }
+
+            levels.push(next);
+        }
+
+        Ok(levels
+            .into_iter()
+            .map(|l| l.into_iter().collect())
+            .collect())
+    }
+
+    /// Runs the robot chain on the human `presses`, returning what gets typed on the first keypad.
+    pub fn simulate(&self, presses: &str) -> Result<String> {
+        let mut arms = self
+            .pads
+            .iter()
+            .map(|pad| pad.coord('A'))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut typed = String::new();
+
+        for press in presses.chars() {
+            let mut key = press;
+            let mut level = self.depth() - 1;
+
+            // a press on a directional pad either moves the arm below it or presses there
+            loop {
+                self.pads[level].coord(key)?;
+
+                if level == 0 {
+                    typed.push(key);
+                    break;
+                }
+
+                level -= 1;
+
+                if let Some((_, delta)) = DIRS.iter().find(|(ins, _)| *ins == key) {
+                    let coord = (arms[level].0 + delta.0, arms[level].1 + delta.1);
+
+                    if !self.pads[level].keys.contains_right(&coord) {
+                        bail!("arm on keypad {level} points at a gap");
+                    }
+
+                    arms[level] = coord;
+                    break;
+                }
+
+                if key != 'A' {
+                    bail!("key {key} on keypad {} does not drive the arm", level + 1);
+                }
+
+                key = *self.pads[level].keys.get_by_right(&arms[level]).unwrap();
+            }
+        }
+
+        Ok(typed)
+    }
+}
+
+/// The door numpad, `robots` arrowpads driven by robots and the human arrowpad.
+pub fn door_chain(robots: usize) -> Result<Chain> {
+    let mut pads = vec![NUMPAD.parse()?];
+
+    for _ in 0..=robots {
+        pads.push(ARROWPAD.parse()?);
+    }
+
+    Chain::new(pads)
+}
+
+pub fn complexity(input: &str, chain: &Chain) -> Result<u64> {
+    let mut res = 0;
+
+    for line in input.lines().filter(|l| !l.is_empty()) {
+        let cost = chain.cost(line)?;
+
+        let code = line.strip_suffix("A").unwrap_or(line).parse::<u64>()?;
+
+        res += code * cost;
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODES: [&str; 5] = ["029A", "980A", "179A", "456A", "379A"];
+
+    #[test]
+    fn layout_test() {
+        let pad: Keypad = NUMPAD.parse().unwrap();
+
+        assert_eq!(pad.coord('7').unwrap(), (0, 0));
+        assert_eq!(pad.coord('A').unwrap(), (2, 3));
+        assert!(pad.coord(' ').is_err());
+        assert!("12\n1A".parse::<Keypad>().is_err());
+    }
+
+    #[test]
+    fn sequences_test() {
+        let chain = door_chain(2).unwrap();
+
+        let levels = chain.sequences("029A").unwrap();
+
+        assert_eq!(levels.len(), 4);
+        assert_eq!(levels[0], "029A");
+        assert_eq!(levels[1].len(), "<A^A>^^AvvvA".len());
+        assert_eq!(levels[3].len(), 68);
+    }
+
+    #[test]
+    fn simulation_test() {
+        let chain = door_chain(2).unwrap();
+
+        for code in CODES {
+            let levels = chain.sequences(code).unwrap();
+            let presses = levels.last().unwrap();
+
+            assert_eq!(chain.simulate(presses).unwrap(), code);
+            assert_eq!(presses.len() as u64, chain.cost(code).unwrap());
+        }
+    }
+
+    #[test]
+    fn custom_chain_test() {
+        let phone: Keypad = "123\n456\n789\n*0A".parse().unwrap();
+        let wide: Keypad = "<^>A\n v  ".parse().unwrap();
+        let arrows: Keypad = ARROWPAD.parse().unwrap();
+
+        let chain = Chain::new(vec![phone, wide, arrows.clone(), arrows]).unwrap();
+
+        let levels = chain.sequences("*90A").unwrap();
+
+        assert_eq!(chain.simulate(levels.last().unwrap()).unwrap(), "*90A");
+        assert_eq!(
+            levels.last().unwrap().len() as u64,
+            chain.cost("*90A").unwrap()
+        );
+
+        let numpad: Keypad = NUMPAD.parse().unwrap();
+        assert!(Chain::new(vec![numpad.clone(), numpad]).is_err());
+    }
+
+    #[test]
+    fn gap_test() {
+        let chain = door_chain(0).unwrap();
+
+        assert!(chain.simulate("<<").is_err());
+    }
+}
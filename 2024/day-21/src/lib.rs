@@ -1,2 +1,3 @@
+pub mod keypad;
 pub mod part_1;
 pub mod part_2;
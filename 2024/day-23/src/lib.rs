@@ -1,5 +1,2 @@
-#![feature(ascii_char)]
-#![feature(let_chains)]
-
 pub mod part_1;
 pub mod part_2;
@@ -1,27 +1,12 @@
 use anyhow::Result;
-use itertools::Itertools;
-use std::collections::HashSet;
+use aoc_graphs::{cliques::k_cliques, NamedUnGraph};
 
 pub fn solve(input: &'static str) -> Result<String> {
-    let mut edges = HashSet::new();
-    let mut nodes = HashSet::new();
+    let named: NamedUnGraph = input.parse()?;
 
-    for (a, b) in input.lines().filter_map(|line| line.split_once("-")) {
-        edges.insert((a, b));
-        edges.insert((b, a));
-        nodes.insert(a);
-        nodes.insert(b);
-    }
-
-    let count = nodes
-        .into_iter()
-        .combinations(3)
-        .filter(|v| {
-            edges.contains(&(v[0], v[1]))
-                && edges.contains(&(v[1], v[2]))
-                && edges.contains(&(v[2], v[0]))
-                && (v[0].starts_with("t") || v[1].starts_with("t") || v[2].starts_with("t"))
-        })
+    let count = k_cliques(&named.graph, 3)
+        .iter()
+        .filter(|clique| clique.iter().any(|&n| named.name(n).starts_with('t')))
         .count();
 
     Ok(count.to_string())
@@ -1,6 +1,5 @@
-use ahash::{HashSet, HashSetExt};
-
-type Range = (u64, u64);
+use ahash::HashSet;
+use aoc_intervals::IntervalSet;
 
 fn main() {
     let s = include_str!("../../input/2025/day5.txt");
@@ -9,59 +8,31 @@ fn main() {
     println!("part 2: {}", solve_p2(s));
 }
 
-fn solve_p1(s: &str) -> usize {
-    let (ranges, ids) = s.split_once("\n\n").unwrap();
-
-    let ids: Vec<u64> = ids.lines().map(|s| s.parse().unwrap()).collect();
-    let ranges: Vec<Range> = ranges
+fn parse_ranges(ranges: &str) -> IntervalSet<u64> {
+    ranges
         .lines()
         .map(|s| s.split_once('-').unwrap())
-        .map(|(i, j)| (i.parse().unwrap(), j.parse().unwrap()))
-        .collect();
+        .map(|(i, j)| i.parse().unwrap()..=j.parse().unwrap())
+        .collect()
+}
 
-    let mut chosen = HashSet::with_capacity(ids.len());
+fn solve_p1(s: &str) -> usize {
+    let (ranges, ids) = s.split_once("\n\n").unwrap();
 
-    for id in ids {
-        for range in &ranges {
-            if range.0 <= id && id <= range.1 {
-                chosen.insert(id);
-            }
-        }
-    }
+    let ranges = parse_ranges(ranges);
 
-    chosen.len()
+    // an id listed twice is still one fresh ingredient
+    ids.lines()
+        .map(|s| s.parse().unwrap())
+        .filter(|&id| ranges.contains(id))
+        .collect::<HashSet<u64>>()
+        .len()
 }
 
 fn solve_p2(s: &str) -> u64 {
     let (ranges, _) = s.split_once("\n\n").unwrap();
 
-    let mut ranges: Vec<Range> = ranges
-        .lines()
-        .map(|s| s.split_once('-').unwrap())
-        .map(|(i, j)| (i.parse().unwrap(), j.parse().unwrap()))
-        .collect();
-
-    ranges.sort_by(|a, b| a.0.cmp(&b.0));
-
-    let mut ranges = ranges.into_iter();
-
-    let mut res = 0;
-
-    let mut current = ranges.next().unwrap();
-
-    for range in ranges {
-        if range.0 <= current.1 {
-            current.1 = range.1.max(current.1)
-        } else {
-            res += current.1 - current.0 + 1;
-            current.0 = range.0;
-            current.1 = range.1;
-        }
-    }
-
-    res += current.1 - current.0 + 1;
-
-    res
+    parse_ranges(ranges).total_len()
 }
 
 #[cfg(test)]
@@ -86,6 +57,11 @@ mod test {
         assert_eq!(solve_p1(SAMPLE), 3)
     }
 
+    #[test]
+    fn repeated_ids_p1() {
+        assert_eq!(solve_p1(&format!("{SAMPLE}5\n17\n1\n")), 3)
+    }
+
     #[test]
     fn sample_p2() {
         assert_eq!(solve_p2(SAMPLE), 14)
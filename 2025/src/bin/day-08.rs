@@ -1,150 +1,82 @@
-use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
-use petgraph::graph::UnGraph;
-use std::collections::BinaryHeap;
+use aoc_geometry::kdtree::KdTree;
+use aoc_graphs::union_find::UnionFind;
 
 fn main() {
     let s = include_str!("../../input/2025/day8.txt");
 
-    println!("part 1: {}", solve_p1(s));
+    println!("part 1: {}", solve_p1(s, 1_000));
     println!("part 2: {}", solve_p2(s));
 }
 
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Default)]
-struct Coords(i64, i64, i64);
+fn parse(s: &str) -> Vec<[i64; 3]> {
+    s.lines()
+        .map(|line| {
+            let mut numbers = line.split(",").map(|n| n.parse().unwrap());
 
-#[derive(Hash, PartialEq, Eq, Clone)]
-struct Pair(Coords, Coords);
-
-impl Ord for Pair {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        distance(&other.0, &other.1).cmp(&distance(&self.0, &self.1))
-    }
-}
-
-impl PartialOrd for Pair {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+            [(); 3].map(|_| numbers.next().unwrap())
+        })
+        .collect()
 }
 
-fn solve_p1(s: &str) -> usize {
-    let mut points: Vec<Coords> = vec![];
+fn solve_p1(s: &str, connections: usize) -> usize {
+    let boxes = parse(s);
+    let tree = KdTree::new(&boxes);
 
-    for line in s.lines() {
-        let mut numbers = line.split(",");
+    let mut circuits = UnionFind::new(boxes.len());
 
-        let x = numbers.next().unwrap().parse().unwrap();
-        let y = numbers.next().unwrap().parse().unwrap();
-        let z = numbers.next().unwrap().parse().unwrap();
-
-        points.push(Coords(x, y, z));
+    for (_, i, j) in tree.closest_pairs().take(connections) {
+        circuits.union(i, j);
     }
 
-    let mut distances = BinaryHeap::<Pair>::with_capacity(points.len() * points.len());
+    circuits.sizes().iter().take(3).product()
+}
 
-    for i in 0..points.len() {
-        for j in 0..i {
-            distances.push(Pair(points[i], points[j]));
-        }
-    }
+/// Connections of a minimum spanning tree as `(distance, i, j)`, by Borůvka: each round every
+/// circuit connects to the closest box of another circuit, so their number at least halves.
+fn spanning_tree(boxes: &[[i64; 3]]) -> Vec<(i64, usize, usize)> {
+    let tree = KdTree::new(boxes);
 
-    let mut edges = HashSet::with_capacity(points.len() * points.len());
+    let mut circuits = UnionFind::new(boxes.len());
+    let mut connections = vec![];
 
-    for _ in 0..1_000 {
-        let p = distances.pop().unwrap();
-        edges.insert(p);
-    }
+    while circuits.count() > 1 {
+        let mut closest: Vec<Option<(i64, usize, usize)>> = vec![None; boxes.len()];
 
-    let mut sizes: Vec<usize> = vec![];
+        // subtrees inside a single circuit are skipped, however large the circuit grows
+        let labels = tree.labels(|i| circuits.find(i));
 
-    let mut remaining = points.clone();
+        for (i, point) in boxes.iter().enumerate() {
+            let circuit = labels.label(i);
 
-    while let Some(point) = remaining.pop() {
-        let mut visited: HashSet<Coords> = HashSet::new();
-        let mut queue: Vec<Coords> = vec![point];
+            let Some((j, distance)) = labels.nearest_other(point, circuit) else {
+                continue;
+            };
 
-        while let Some(point) = queue.pop() {
-            if !visited.contains(&point) {
-                let e = edges.iter().filter(|p| p.0 == point || p.1 == point);
+            // same order as sorting every pair, so ties pick the same tree Kruskal would
+            let connection = (distance, i.min(j), i.max(j));
 
-                for p in e {
-                    queue.push(p.0);
-                    queue.push(p.1);
-                }
+            if closest[circuit].is_none_or(|c| connection < c) {
+                closest[circuit] = Some(connection);
             }
-
-            visited.insert(point);
         }
 
-        sizes.push(visited.len());
-
-        remaining.retain(|c| !visited.contains(c))
-    }
-
-    // dbg!(&sizes);
-
-    sizes.sort();
-
-    sizes.iter().rev().take(3).product()
-}
-
-fn distance(rhs: &Coords, lhs: &Coords) -> i64 {
-    (rhs.0 - lhs.0).pow(2) + (rhs.1 - lhs.1).pow(2) + (rhs.2 - lhs.2).pow(2)
-}
-
-fn solve_p2(s: &str) -> usize {
-    let mut points: Vec<Coords> = vec![];
-
-    for line in s.lines() {
-        let mut numbers = line.split(",");
-
-        let x = numbers.next().unwrap().parse().unwrap();
-        let y = numbers.next().unwrap().parse().unwrap();
-        let z = numbers.next().unwrap().parse().unwrap();
-
-        points.push(Coords(x, y, z));
-    }
-
-    let mut distances = BinaryHeap::<Pair>::with_capacity(points.len() * points.len());
-
-    for i in 0..points.len() {
-        for j in 0..i {
-            distances.push(Pair(points[i], points[j]));
+        for (distance, i, j) in closest.into_iter().flatten() {
+            if circuits.union(i, j) {
+                connections.push((distance, i, j));
+            }
         }
     }
 
-    let mut edges = HashSet::with_capacity(points.len() * points.len());
-
-    let point = points.first().unwrap();
-
-    while let Some(p) = distances.pop() {
-        let q = p.clone();
-        edges.insert(p);
-
-        let mut visited: HashSet<Coords> = HashSet::new();
-        let mut queue = vec![*point];
-
-        while let Some(point) = queue.pop() {
-            if !visited.contains(&point) {
-                let e = edges.iter().filter(|p| p.0 == point || p.1 == point);
-
-                for p in e {
-                    queue.push(p.0);
-                    queue.push(p.1);
-                }
-            }
+    connections
+}
 
-            visited.insert(point);
-        }
+fn solve_p2(s: &str) -> i64 {
+    let boxes = parse(s);
 
-        if visited.len() == points.len() {
-            return (q.0.0 * q.1.0) as usize;
-        } else {
-            // println!("{}", points.len() - visited.len())
-        }
-    }
+    // Kruskal would add the longest connection of the tree last
+    let (_, i, j) = spanning_tree(&boxes).into_iter().max().unwrap();
 
-    unreachable!();
+    boxes[i][0] * boxes[j][0]
 }
 
 #[cfg(test)]
@@ -174,11 +106,10 @@ mod test {
 
     #[test]
     fn sample_p1() {
-        assert_eq!(solve_p1(SAMPLE), 40)
+        assert_eq!(solve_p1(SAMPLE, 10), 40)
     }
 
     #[test]
-    #[ignore]
     fn sample_p2() {
         assert_eq!(solve_p2(SAMPLE), 25272)
     }
@@ -1,4 +1,4 @@
-use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use aoc_geometry::Polygon;
 
 fn main() {
     let s = include_str!("../../input/2025/day9.txt");
@@ -39,18 +39,23 @@ fn compute_area(a: &(usize, usize), b: &(usize, usize)) -> usize {
     (q.0 - p.0 + 1) * (q.1 - p.1 + 1)
 }
 
-fn solve_p2(s: &str) -> u64 {
+fn solve_p2(s: &str) -> i64 {
     let mut points = vec![];
 
     for line in s.lines() {
         let coords = line.split_once(',').unwrap();
 
-        let x: u64 = coords.0.parse().unwrap();
-        let y: u64 = coords.1.parse().unwrap();
+        let x: i64 = coords.0.parse().unwrap();
+        let y: i64 = coords.1.parse().unwrap();
 
         points.push((x, y));
     }
 
+    let polygon = Polygon::new(points.clone());
+
+    // centers of the rectangles land on half integers
+    let doubled = polygon.scaled(2);
+
     let mut rectangles = Vec::with_capacity(points.len() * points.len());
 
     for i in 0..points.len() {
@@ -64,24 +69,17 @@ fn solve_p2(s: &str) -> u64 {
 
     rectangles.sort_by_key(|(_, _, a)| *a);
 
-    for (a, b, area) in rectangles.iter().rev() {
-        let mut intersects = false;
-
-        let n = points.len();
+    points.push(*points.first().unwrap());
 
-        points.push(*points.first().unwrap());
-
-        for i in 0..n {
-            let (p, q) = normalize(&points[i], &points[i + 1]);
+    for (a, b, area) in rectangles.iter().rev() {
+        let intersects = points.windows(2).any(|edge| {
+            let (p, q) = normalize(&edge[0], &edge[1]);
 
-            if !(b.0 <= p.0 || a.0 >= q.0 || b.1 <= p.1 || a.1 >= p.1) {
-                intersects = true;
-                break;
-            }
-        }
+            !(b.0 <= p.0 || a.0 >= q.0 || b.1 <= p.1 || a.1 >= q.1)
+        });
 
-        if !intersects {
-            // && is_interior(&points, &(a.0 + 1, a.1 + 1)) {
+        // no edge goes through the rectangle, so it is either all inside or all outside
+        if !intersects && doubled.contains((a.0 + b.0, a.1 + b.1)) {
             return *area;
         }
     }
@@ -89,56 +87,10 @@ fn solve_p2(s: &str) -> u64 {
     unreachable!()
 }
 
-fn normalize(p: &(u64, u64), q: &(u64, u64)) -> ((u64, u64), (u64, u64)) {
+fn normalize(p: &(i64, i64), q: &(i64, i64)) -> ((i64, i64), (i64, i64)) {
     ((p.0.min(q.0), p.1.min(q.1)), (p.0.max(q.0), p.1.max(q.1)))
 }
 
-fn is_interior(points: &[(u64, u64)], p: &(u64, u64)) -> bool {
-    // assumes 0,0 is outside
-
-    let mut crossings = 0;
-
-    let d = p.0.min(p.1);
-
-    let mut q = (p.0 - d, p.1 - d);
-
-    for _ in 1..=d {
-        q = (q.0 + 1, q.1 + 1);
-        if is_border(points, &q) {
-            crossings += 1;
-        }
-    }
-
-    if (crossings % 2) == 1 {
-        return true;
-    }
-
-    false
-}
-
-fn is_border(points: &[(u64, u64)], p: &(u64, u64)) -> bool {
-    if points.contains(p) {
-        return true;
-    }
-
-    for i in 0..points.len() {
-        for j in 0..points.len() {
-            let a = points[i];
-            let b = points[j];
-
-            if a.0 == b.0 && p.0 == a.0 && a.1.min(b.1) < p.1 && p.1 < a.1.max(b.1) {
-                return true;
-            }
-
-            if a.1 == b.1 && p.1 == a.1 && a.0.min(b.0) < p.0 && p.0 < a.0.max(b.0) {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
 #[cfg(test)]
 mod test {
     use crate::*;
@@ -0,0 +1,317 @@
+//! Nearest neighbours of points in `K` dimensions.
+//!
+//! The tree is a sorted copy of the points, each slice split at its median along one axis after
+//! the other, so it needs no more memory than the points themselves.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+pub fn squared_distance<const K: usize>(a: &[i64; K], b: &[i64; K]) -> i64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).pow(2)).sum()
+}
+
+/// Points split along each axis in turn, each one tagged with its position in the input.
+#[derive(Debug, Clone)]
+pub struct KdTree<const K: usize> {
+    nodes: Vec<([i64; K], usize)>,
+}
+
+impl<const K: usize> KdTree<K> {
+    pub fn new(points: &[[i64; K]]) -> Self {
+        fn build<const K: usize>(nodes: &mut [([i64; K], usize)], axis: usize) {
+            if nodes.len() <= 1 {
+                return;
+            }
+
+            let mid = nodes.len() / 2;
+            nodes.select_nth_unstable_by_key(mid, |(point, _)| point[axis]);
+
+            let (below, above) = nodes.split_at_mut(mid);
+            build(below, (axis + 1) % K);
+            build(&mut above[1..], (axis + 1) % K);
+        }
+
+        let mut nodes = points.iter().copied().zip(0..).collect::<Vec<_>>();
+        build(&mut nodes, 0);
+
+        KdTree { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Closest point, by squared distance and then by index, that `accept` takes given its index
+    /// and squared distance. Returns the index and the squared distance.
+    ///
+    /// Every point `accept` turns down is still visited, so rejecting most of them makes for a
+    /// slow search, [`Labels::nearest_other`] skips whole subtrees instead.
+    pub fn nearest_by(
+        &self,
+        query: &[i64; K],
+        accept: impl FnMut(usize, i64) -> bool,
+    ) -> Option<(usize, i64)> {
+        self.search(query, |_| false, accept)
+    }
+
+    /// Like [`KdTree::nearest_by`], without looking into the subtree rooted at each position of
+    /// `nodes` that `skip` takes.
+    fn search(
+        &self,
+        query: &[i64; K],
+        skip: impl Fn(usize) -> bool,
+        mut accept: impl FnMut(usize, i64) -> bool,
+    ) -> Option<(usize, i64)> {
+        fn search<const K: usize>(
+            nodes: &[([i64; K], usize)],
+            offset: usize,
+            axis: usize,
+            query: &[i64; K],
+            skip: &impl Fn(usize) -> bool,
+            accept: &mut impl FnMut(usize, i64) -> bool,
+            best: &mut Option<(i64, usize)>,
+        ) {
+            let mid = nodes.len() / 2;
+
+            if nodes.is_empty() || skip(offset + mid) {
+                return;
+            }
+
+            let (point, index) = nodes[mid];
+
+            let distance = squared_distance(&point, query);
+
+            if best.is_none_or(|best| (distance, index) < best) && accept(index, distance) {
+                *best = Some((distance, index));
+            }
+
+            let below = (&nodes[..mid], offset);
+            let above = (&nodes[mid + 1..], offset + mid + 1);
+            let split = query[axis] - point[axis];
+
+            let (near, far) = if split < 0 {
+                (below, above)
+            } else {
+                (above, below)
+            };
+
+            let next = (axis + 1) % K;
+
+            search(near.0, near.1, next, query, skip, accept, best);
+
+            // points across the split are at least `split` away
+            if best.is_none_or(|(best, _)| split.pow(2) <= best) {
+                search(far.0, far.1, next, query, skip, accept, best);
+            }
+        }
+
+        let mut best = None;
+        search(&self.nodes, 0, 0, query, &skip, &mut accept, &mut best);
+
+        best.map(|(distance, index)| (index, distance))
+    }
+
+    pub fn nearest(&self, query: &[i64; K]) -> Option<(usize, i64)> {
+        self.nearest_by(query, |_, _| true)
+    }
+
+    /// Every pair of points `(distance, i, j)` with `i < j`, closest first, ties by index.
+    ///
+    /// Only the next neighbour of each point is kept around, so taking the first few pairs is
+    /// cheap however many points there are. Each point looks at all the others, as skipping
+    /// the ones before it would turn down most points late in the input, so every pair comes
+    /// up twice and the copy with `i > j` is dropped.
+    pub fn closest_pairs(&self) -> impl Iterator<Item = (i64, usize, usize)> + '_ {
+        let mut points = vec![[0; K]; self.len()];
+
+        for &(point, index) in &self.nodes {
+            points[index] = point;
+        }
+
+        // the neighbour of `i` after `j` at `distance`
+        let next = move |i: usize, after: Option<(i64, usize)>| {
+            self.nearest_by(&points[i], |j, distance| {
+                i != j && after.is_none_or(|after| (distance, j) > after)
+            })
+            .map(|(j, distance)| Reverse((distance, i, j)))
+        };
+
+        let mut heap = (0..self.len())
+            .filter_map(|i| next(i, None))
+            .collect::<BinaryHeap<_>>();
+
+        std::iter::from_fn(move || loop {
+            let Reverse((distance, i, j)) = heap.pop()?;
+
+            heap.extend(next(i, Some((distance, j))));
+
+            if i < j {
+                return Some((distance, i, j));
+            }
+        })
+    }
+
+    /// Gives each point the label `label` returns for its index, such as the group it is in,
+    /// and notes the subtrees where every point has the same one.
+    pub fn labels(&self, label: impl FnMut(usize) -> usize) -> Labels<'_, K> {
+        fn shared<const K: usize>(
+            nodes: &[([i64; K], usize)],
+            offset: usize,
+            labels: &[usize],
+            res: &mut [Option<usize>],
+        ) -> Option<Option<usize>> {
+            if nodes.is_empty() {
+                return None;
+            }
+
+            let mid = nodes.len() / 2;
+
+            let below = shared(&nodes[..mid], offset, labels, res);
+            let above = shared(&nodes[mid + 1..], offset + mid + 1, labels, res);
+
+            let own = Some(labels[nodes[mid].1]);
+            let label = [below, above]
+                .into_iter()
+                .flatten()
+                .fold(own, |acc, label| if acc == label { acc } else { None });
+
+            res[offset + mid] = label;
+            Some(label)
+        }
+
+        let labels = (0..self.len()).map(label).collect::<Vec<_>>();
+
+        let mut subtrees = vec![None; self.len()];
+        shared(&self.nodes, 0, &labels, &mut subtrees);
+
+        Labels {
+            tree: self,
+            labels,
+            subtrees,
+        }
+    }
+}
+
+/// A label for every point of a tree, and for every subtree the label all its points share,
+/// if they do.
+#[derive(Debug, Clone)]
+pub struct Labels<'a, const K: usize> {
+    tree: &'a KdTree<K>,
+    labels: Vec<usize>,
+    subtrees: Vec<Option<usize>>,
+}
+
+impl<const K: usize> Labels<'_, K> {
+    pub fn label(&self, index: usize) -> usize {
+        self.labels[index]
+    }
+
+    /// Closest point with a label other than `label`, as [`KdTree::nearest_by`] would find it,
+    /// without going into any subtree that only has points labelled `label`.
+    pub fn nearest_other(&self, query: &[i64; K], label: usize) -> Option<(usize, i64)> {
+        self.tree.search(
+            query,
+            |position| self.subtrees[position] == Some(label),
+            |index, _| self.labels[index] != label,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every point with its coordinates taken from `xs`, `ys` and `zs`.
+    fn lattice(xs: &[i64], ys: &[i64], zs: &[i64]) -> Vec<[i64; 3]> {
+        xs.iter()
+            .flat_map(|&x| {
+                ys.iter()
+                    .flat_map(move |&y| zs.iter().map(move |&z| [x, y, z]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nearest_test() {
+        // unevenly spaced, with queries on and between the points
+        let points = lattice(
+            &[0, 3, 11, 20, 34, 49],
+            &[1, 8, 16, 25, 27, 40],
+            &[0, 13, 22, 30, 45],
+        );
+        let tree = KdTree::new(&points);
+
+        let queries = [5, 19, 30, 47];
+
+        for query in lattice(&queries, &queries, &queries) {
+            let expected = (0..points.len())
+                .map(|i| (squared_distance(&points[i], &query), i))
+                .min()
+                .map(|(distance, i)| (i, distance));
+
+            assert_eq!(tree.nearest(&query), expected);
+
+            // only points on one side
+            let expected = (0..points.len())
+                .filter(|&i| points[i][1] > 25)
+                .map(|i| (squared_distance(&points[i], &query), i))
+                .min()
+                .map(|(distance, i)| (i, distance));
+
+            assert_eq!(tree.nearest_by(&query, |i, _| points[i][1] > 25), expected);
+        }
+
+        assert_eq!(KdTree::<3>::new(&[]).nearest(&[0, 0, 0]), None);
+    }
+
+    #[test]
+    fn labels_test() {
+        let points = lattice(&[0, 3, 11, 20], &[1, 8, 16, 25], &[0, 13, 22]);
+        let tree = KdTree::new(&points);
+
+        // one large group on the low side, and a few scattered ones
+        let group = |i: usize| {
+            if points[i][0] < 20 && points[i][2] > 0 {
+                0
+            } else {
+                i % 5 + 1
+            }
+        };
+
+        let labels = tree.labels(group);
+
+        for (i, point) in points.iter().enumerate() {
+            let label = labels.label(i);
+            assert_eq!(label, group(i));
+
+            let expected = tree.nearest_by(point, |j, _| group(j) != label);
+            assert_eq!(labels.nearest_other(point, label), expected);
+        }
+
+        let same = tree.labels(|_| 7);
+        assert_eq!(same.nearest_other(&points[0], 7), None);
+        assert_eq!(same.nearest_other(&points[0], 8), Some((0, 0)));
+    }
+
+    #[test]
+    fn closest_pairs_test() {
+        // plenty of repeated points and equal distances
+        let points = (0..120).map(|i| [i % 5, i % 4, i % 3]).collect::<Vec<_>>();
+
+        let mut expected = vec![];
+
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                expected.push((squared_distance(&points[i], &points[j]), i, j));
+            }
+        }
+
+        expected.sort();
+
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.closest_pairs().collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,205 @@
+//! Polygons with integer vertices, as drawn by a loop of pipes or a dig plan.
+//!
+//! The area comes from the shoelace formula and Pick's theorem relates it to the lattice points
+//! on and inside the boundary, so enclosed tiles can be counted without flooding a grid.
+
+pub mod kdtree;
+
+pub type Point = (i64, i64);
+
+/// Where a point lies with respect to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A simple closed polygon, the last vertex connects back to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// Rectilinear polygon walked from the origin, moving `len` times along each unit `dir`.
+    pub fn from_steps(steps: impl IntoIterator<Item = (Point, i64)>) -> Self {
+        let mut cur = (0, 0);
+
+        let vertices = steps
+            .into_iter()
+            .map(|(dir, len)| {
+                cur = (cur.0 + dir.0 * len, cur.1 + dir.1 * len);
+                cur
+            })
+            .collect();
+
+        Polygon { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Whether every edge is either horizontal or vertical.
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges().all(|(p, q)| p.0 == q.0 || p.1 == q.1)
+    }
+
+    /// Same polygon with every coordinate multiplied by `k`, so that half integer points of the
+    /// original are lattice points of the scaled one.
+    pub fn scaled(&self, k: i64) -> Self {
+        Polygon::new(self.vertices.iter().map(|p| (p.0 * k, p.1 * k)).collect())
+    }
+
+    /// Twice the signed area, positive when the vertices turn counterclockwise with the y axis
+    /// pointing up. Doubled so it stays an integer.
+    pub fn doubled_signed_area(&self) -> i64 {
+        // Shoelace (Trapezoid) formula
+        self.edges().map(|(p, q)| (p.0 + q.0) * (q.1 - p.1)).sum()
+    }
+
+    /// Area, rounded down when it is a half integer.
+    pub fn area(&self) -> i64 {
+        self.doubled_signed_area().abs() / 2
+    }
+
+    /// Lattice points on the edges, vertices included.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges().map(|(p, q)| gcd(q.0 - p.0, q.1 - p.1)).sum()
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i64 {
+        (self.doubled_signed_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// Lattice points inside or on the boundary.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Crossing number of a ray cast towards positive x, checking the boundary first.
+    pub fn locate(&self, point: Point) -> Location {
+        let (x, y) = point;
+        let mut inside = false;
+
+        for (p, q) in self.edges() {
+            let cross =
+                (q.0 - p.0) as i128 * (y - p.1) as i128 - (q.1 - p.1) as i128 * (x - p.0) as i128;
+
+            if cross == 0
+                && p.0.min(q.0) <= x
+                && x <= p.0.max(q.0)
+                && p.1.min(q.1) <= y
+                && y <= p.1.max(q.1)
+            {
+                return Location::Boundary;
+            }
+
+            // half open on y so a ray through a vertex is only counted once, the crossing is to
+            // the right of the point when the cross product has the same sign as the edge
+            if (p.1 > y) != (q.1 > y) && (cross > 0) == (q.1 > p.1) {
+                inside = !inside;
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Whether the point is inside or on the boundary.
+    pub fn contains(&self, point: Point) -> bool {
+        self.locate(point) != Location::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Polygon {
+        Polygon::from_steps([((1, 0), 4), ((0, 1), 4), ((-1, 0), 4), ((0, -1), 4)])
+    }
+
+    #[test]
+    fn area_test() {
+        let square = square();
+
+        assert!(square.is_rectilinear());
+        assert_eq!(square.doubled_signed_area(), 32);
+        assert_eq!(square.area(), 16);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.lattice_points(), 25);
+
+        let reversed = Polygon::new(square.vertices.iter().rev().copied().collect());
+        assert_eq!(reversed.doubled_signed_area(), -32);
+        assert_eq!(reversed.interior_points(), 9);
+    }
+
+    #[test]
+    fn pick_test() {
+        // a triangle with a slanted edge, the hypotenuse only hits 3 lattice points
+        let triangle = Polygon::new(vec![(0, 0), (4, 0), (0, 2)]);
+
+        assert!(!triangle.is_rectilinear());
+        assert_eq!(triangle.doubled_signed_area(), 8);
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 1);
+
+        // brute force over the bounding box
+        let counted = (0..=4)
+            .flat_map(|x| (0..=2).map(move |y| (x, y)))
+            .filter(|&p| triangle.locate(p) == Location::Inside)
+            .count();
+        assert_eq!(counted, 1);
+    }
+
+    #[test]
+    fn locate_test() {
+        // a U shape, so rays from the notch cross the boundary several times
+        let u = Polygon::new(vec![
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (4, 4),
+            (4, 2),
+            (2, 2),
+            (2, 4),
+            (0, 4),
+        ]);
+
+        assert_eq!(u.locate((1, 1)), Location::Inside);
+        assert_eq!(u.locate((5, 3)), Location::Inside);
+        assert_eq!(u.locate((3, 3)), Location::Outside);
+        assert_eq!(u.locate((1, 4)), Location::Boundary);
+        assert_eq!(u.locate((4, 2)), Location::Boundary);
+        assert_eq!(u.locate((-1, 2)), Location::Outside);
+        assert_eq!(u.locate((7, 0)), Location::Outside);
+        // the ray passes through the vertices at y = 2
+        assert_eq!(u.locate((1, 2)), Location::Inside);
+
+        let doubled = u.scaled(2);
+        assert!(doubled.contains((9, 7)));
+        assert!(!doubled.contains((7, 7)));
+    }
+}
@@ -0,0 +1,298 @@
+//! Cliques of undirected graphs.
+//!
+//! Nodes are visited in degeneracy order, so each one only ever looks at the few neighbours
+//! that come after it, however large the graph is.
+
+use petgraph::{
+    graph::{Graph, NodeIndex},
+    Undirected,
+};
+use std::collections::HashSet;
+
+type Neighbours = Vec<HashSet<usize>>;
+
+fn neighbours<N, E>(graph: &Graph<N, E, Undirected>) -> Neighbours {
+    graph
+        .node_indices()
+        .map(|n| {
+            graph
+                .neighbors(n)
+                .map(|m| m.index())
+                .filter(|&m| m != n.index())
+                .collect()
+        })
+        .collect()
+}
+
+/// Position of each node when repeatedly taking out one of smallest degree.
+fn degeneracy_ranks(neighbours: &Neighbours) -> Vec<usize> {
+    let mut degrees = neighbours.iter().map(HashSet::len).collect::<Vec<_>>();
+    let mut buckets = vec![HashSet::new(); degrees.iter().max().map_or(0, |d| d + 1)];
+
+    for (n, &degree) in degrees.iter().enumerate() {
+        buckets[degree].insert(n);
+    }
+
+    let mut ranks = vec![usize::MAX; neighbours.len()];
+
+    for rank in 0..neighbours.len() {
+        let degree = buckets.iter().position(|b| !b.is_empty()).unwrap();
+        let n = *buckets[degree].iter().next().unwrap();
+
+        buckets[degree].remove(&n);
+        ranks[n] = rank;
+
+        for &m in &neighbours[n] {
+            if ranks[m] == usize::MAX {
+                buckets[degrees[m]].remove(&m);
+                degrees[m] -= 1;
+                buckets[degrees[m]].insert(m);
+            }
+        }
+    }
+
+    ranks
+}
+
+/// Bron–Kerbosch, skipping the neighbours of a pivot that has the most of them in `p`.
+fn bron_kerbosch(
+    neighbours: &Neighbours,
+    r: &mut Vec<usize>,
+    mut p: HashSet<usize>,
+    mut x: HashSet<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    let Some(&pivot) = p
+        .iter()
+        .chain(&x)
+        .max_by_key(|&&u| neighbours[u].intersection(&p).count())
+    else {
+        cliques.push(r.clone());
+        return;
+    };
+
+    let candidates = p
+        .difference(&neighbours[pivot])
+        .copied()
+        .collect::<Vec<_>>();
+
+    for v in candidates {
+        r.push(v);
+        bron_kerbosch(
+            neighbours,
+            r,
+            p.intersection(&neighbours[v]).copied().collect(),
+            x.intersection(&neighbours[v]).copied().collect(),
+            cliques,
+        );
+        r.pop();
+
+        p.remove(&v);
+        x.insert(v);
+    }
+}
+
+fn to_nodes(cliques: Vec<Vec<usize>>) -> Vec<Vec<NodeIndex>> {
+    cliques
+        .into_iter()
+        .map(|clique| {
+            let mut clique = clique.into_iter().map(NodeIndex::new).collect::<Vec<_>>();
+            clique.sort();
+            clique
+        })
+        .collect()
+}
+
+/// Every clique that can not be grown any further, each with its nodes sorted.
+pub fn maximal_cliques<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<Vec<NodeIndex>> {
+    let neighbours = neighbours(graph);
+    let ranks = degeneracy_ranks(&neighbours);
+
+    let mut cliques = vec![];
+
+    for v in 0..neighbours.len() {
+        let (p, x) = neighbours[v].iter().partition(|&&m| ranks[m] > ranks[v]);
+
+        bron_kerbosch(&neighbours, &mut vec![v], p, x, &mut cliques);
+    }
+
+    to_nodes(cliques)
+}
+
+/// A largest clique, the first one in node order among those of the same size.
+pub fn maximum_clique<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    maximal_cliques(graph)
+        .into_iter()
+        .max_by(|a, b| a.len().cmp(&b.len()).then(b.cmp(a)))
+        .unwrap_or_default()
+}
+
+/// Every clique of exactly `k` nodes, maximal or not, each with its nodes sorted.
+pub fn k_cliques<N, E>(graph: &Graph<N, E, Undirected>, k: usize) -> Vec<Vec<NodeIndex>> {
+    fn extend(
+        later: &[HashSet<usize>],
+        clique: &mut Vec<usize>,
+        candidates: &HashSet<usize>,
+        k: usize,
+        cliques: &mut Vec<Vec<usize>>,
+    ) {
+        if clique.len() == k {
+            cliques.push(clique.clone());
+            return;
+        }
+
+        for &v in candidates {
+            clique.push(v);
+            extend(
+                later,
+                clique,
+                &candidates.intersection(&later[v]).copied().collect(),
+                k,
+                cliques,
+            );
+            clique.pop();
+        }
+    }
+
+    let neighbours = neighbours(graph);
+    let ranks = degeneracy_ranks(&neighbours);
+
+    // each clique is only found from its earliest node, growing through later ones
+    let later = neighbours
+        .iter()
+        .enumerate()
+        .map(|(n, ms)| {
+            ms.iter()
+                .copied()
+                .filter(|&m| ranks[m] > ranks[n])
+                .collect()
+        })
+        .collect::<Vec<HashSet<_>>>();
+
+    let mut cliques = vec![];
+
+    if k > 0 {
+        for v in 0..neighbours.len() {
+            extend(&later, &mut vec![v], &later[v], k, &mut cliques);
+        }
+    }
+
+    let mut cliques = to_nodes(cliques);
+    cliques.sort();
+    cliques
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedUnGraph;
+
+    /// The graph on `nodes` nodes joining the pairs whose bit is set in `edges`, pairs
+    /// numbered in order, `0-1`, `0-2`, ..., `1-2`, ...
+    fn graph(nodes: usize, edges: u64) -> Graph<(), (), Undirected> {
+        let mut graph = Graph::new_undirected();
+
+        let nodes = (0..nodes).map(|_| graph.add_node(())).collect::<Vec<_>>();
+
+        let pairs = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &a)| nodes[i + 1..].iter().map(move |&b| (a, b)));
+
+        for (bit, (a, b)) in pairs.enumerate() {
+            if edges & (1 << bit) != 0 {
+                graph.add_edge(a, b, ());
+            }
+        }
+
+        graph
+    }
+
+    fn is_clique(graph: &Graph<(), (), Undirected>, nodes: &[NodeIndex]) -> bool {
+        nodes
+            .iter()
+            .enumerate()
+            .all(|(i, &a)| nodes[i + 1..].iter().all(|&b| graph.contains_edge(a, b)))
+    }
+
+    /// Every subset of nodes that is a clique, by brute force.
+    fn all_cliques(graph: &Graph<(), (), Undirected>) -> Vec<Vec<NodeIndex>> {
+        let nodes = graph.node_indices().collect::<Vec<_>>();
+
+        (1..1_u32 << nodes.len())
+            .map(|mask| {
+                nodes
+                    .iter()
+                    .filter(|n| mask & (1 << n.index()) != 0)
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|clique| is_clique(graph, clique))
+            .collect()
+    }
+
+    #[test]
+    fn cliques_test() {
+        let named: NamedUnGraph = "a-b\nb-c\nc-a\nc-d\nd-e\ne-c\nd-f".parse().unwrap();
+
+        let names = |clique: &Vec<NodeIndex>| {
+            clique
+                .iter()
+                .map(|&n| named.name(n))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut maximal = maximal_cliques(&named.graph)
+            .iter()
+            .map(names)
+            .collect::<Vec<_>>();
+        maximal.sort();
+
+        assert_eq!(maximal, ["a,b,c", "c,d,e", "d,f"]);
+        assert_eq!(names(&maximum_clique(&named.graph)), "a,b,c");
+        assert_eq!(k_cliques(&named.graph, 2).len(), 7);
+        assert_eq!(k_cliques(&named.graph, 4), Vec::<Vec<NodeIndex>>::new());
+    }
+
+    #[test]
+    fn brute_force_test() {
+        // every graph on 5 nodes, and a spread of the ones on 7
+        let graphs = (0..1 << 10)
+            .map(|edges| graph(5, edges))
+            .chain((0..1 << 21).step_by(4099).map(|edges| graph(7, edges)));
+
+        for graph in graphs {
+            let all = all_cliques(&graph);
+
+            let mut expected = all
+                .iter()
+                .filter(|a| {
+                    !all.iter()
+                        .any(|b| b.len() > a.len() && a.iter().all(|n| b.contains(n)))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            expected.sort();
+
+            let mut maximal = maximal_cliques(&graph);
+            maximal.sort();
+
+            assert_eq!(maximal, expected);
+
+            let largest = all.iter().map(Vec::len).max().unwrap_or(0);
+            assert_eq!(maximum_clique(&graph).len(), largest);
+
+            for k in 1..=4 {
+                let mut expected = all
+                    .iter()
+                    .filter(|c| c.len() == k)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                expected.sort();
+
+                assert_eq!(k_cliques(&graph, k), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Graphs given as lists of named nodes and their neighbours, loaded into petgraph.
+//!
+//! Any line can be `a-b`, `a: b c` or `a -> b, c`, and names map to node indices and back so
+//! answers can always be given with the names of the input.
+
+pub mod cliques;
+pub mod paths;
+pub mod union_find;
+
+pub use petgraph;
+
+use anyhow::{bail, Error, Result};
+use petgraph::{
+    graph::{EdgeIndex, Graph, NodeIndex},
+    Directed, EdgeType, Undirected,
+};
+use std::{collections::HashMap, str::FromStr};
+
+/// A petgraph graph whose nodes are weighted with their names.
+#[derive(Debug, Clone)]
+pub struct NamedGraph<E = (), Ty: EdgeType = Directed> {
+    pub graph: Graph<String, E, Ty>,
+    indices: HashMap<String, NodeIndex>,
+}
+
+pub type NamedDiGraph<E = ()> = NamedGraph<E, Directed>;
+pub type NamedUnGraph<E = ()> = NamedGraph<E, Undirected>;
+
+impl<E, Ty: EdgeType> Default for NamedGraph<E, Ty> {
+    fn default() -> Self {
+        NamedGraph {
+            graph: Graph::default(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<E, Ty: EdgeType> NamedGraph<E, Ty> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Node with this name, added if it is not there yet.
+    pub fn node(&mut self, name: &str) -> NodeIndex {
+        if let Some(&node) = self.indices.get(name) {
+            return node;
+        }
+
+        let node = self.graph.add_node(name.to_string());
+        self.indices.insert(name.to_string(), node);
+        node
+    }
+
+    pub fn index(&self, name: &str) -> Option<NodeIndex> {
+        self.indices.get(name).copied()
+    }
+
+    pub fn name(&self, node: NodeIndex) -> &str {
+        &self.graph[node]
+    }
+
+    /// Edge between the named nodes, an existing one only gets its weight replaced.
+    pub fn add_edge(&mut self, a: &str, b: &str, weight: E) -> EdgeIndex {
+        let (a, b) = (self.node(a), self.node(b));
+        self.graph.update_edge(a, b, weight)
+    }
+}
+
+/// A node and the neighbours listed for it on one line, `None` for blank lines.
+fn parse_line(line: &str) -> Result<Option<(&str, Vec<&str>)>> {
+    let line = line.trim();
+
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    // `a:` and `a ->` are nodes without neighbours
+    let (node, neighbours) = if let Some((node, neighbours)) = line.split_once("->") {
+        match neighbours.trim() {
+            "" => (node, vec![]),
+            neighbours => (node, neighbours.split(',').collect::<Vec<_>>()),
+        }
+    } else if let Some((node, neighbours)) = line.split_once(':') {
+        (node, neighbours.split_whitespace().collect())
+    } else if let Some((node, neighbour)) = line.split_once('-') {
+        (node, vec![neighbour])
+    } else {
+        bail!("expected `a-b`, `a: b c` or `a -> b, c`, found `{line}`");
+    };
+
+    let node = node.trim();
+    let neighbours = neighbours.into_iter().map(str::trim).collect::<Vec<_>>();
+
+    if node.is_empty() || neighbours.contains(&"") {
+        bail!("missing a name in `{line}`");
+    }
+
+    Ok(Some((node, neighbours)))
+}
+
+impl<Ty: EdgeType> FromStr for NamedGraph<(), Ty> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut named = NamedGraph::new();
+
+        for line in s.lines() {
+            let Some((node, neighbours)) = parse_line(line)? else {
+                continue;
+            };
+
+            named.node(node);
+
+            for neighbour in neighbours {
+                named.add_edge(node, neighbour, ());
+            }
+        }
+
+        Ok(named)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges<Ty: EdgeType>(named: &NamedGraph<(), Ty>) -> Vec<(&str, &str)> {
+        let mut edges = named
+            .graph
+            .edge_indices()
+            .map(|e| {
+                let (a, b) = named.graph.edge_endpoints(e).unwrap();
+                (named.name(a), named.name(b))
+            })
+            .collect::<Vec<_>>();
+
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn formats_test() {
+        let dashes: NamedUnGraph = "kh-tc\nqp-kh\n\ntc-kh".parse().unwrap();
+        assert_eq!(edges(&dashes), [("kh", "tc"), ("qp", "kh")]);
+
+        let colons: NamedDiGraph = "you: bbb ccc\nbbb: out\nout:".parse().unwrap();
+        assert_eq!(
+            edges(&colons),
+            [("bbb", "out"), ("you", "bbb"), ("you", "ccc")]
+        );
+
+        let arrows: NamedDiGraph = "fwft -> ktlj, cntj\nktlj ->".parse().unwrap();
+        assert_eq!(edges(&arrows), [("fwft", "cntj"), ("fwft", "ktlj")]);
+
+        // the same list, undirected
+        let arrows: NamedUnGraph = "a -> b\nb -> a".parse().unwrap();
+        assert_eq!(arrows.graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn names_test() {
+        let named: NamedDiGraph = "svr: aaa bbb\naaa: svr".parse().unwrap();
+
+        assert_eq!(named.graph.node_count(), 3);
+
+        for name in ["svr", "aaa", "bbb"] {
+            assert_eq!(named.name(named.index(name).unwrap()), name);
+        }
+
+        assert_eq!(named.index("out"), None);
+    }
+
+    #[test]
+    fn errors_test() {
+        assert!("a b".parse::<NamedDiGraph>().is_err());
+        assert!("a-".parse::<NamedDiGraph>().is_err());
+        assert!(": b".parse::<NamedDiGraph>().is_err());
+        assert!("a -> b,".parse::<NamedDiGraph>().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! Counting paths of directed acyclic graphs.
+//!
+//! Nodes are taken in topological order, each one holding how many paths reach it for every
+//! subset of the mandatory nodes seen on the way, so the whole count takes a single pass.
+
+use anyhow::{bail, Context, Result};
+use petgraph::{
+    algo::toposort,
+    graph::{Graph, NodeIndex},
+    Directed,
+};
+use std::fmt::Debug;
+
+/// Most counts kept at once, one for every node and subset of the mandatory nodes, so each
+/// mandatory node halves how large the graph can be. 64 MiB worth of `Option<u64>`.
+pub const MAX_COUNTS: usize = 1 << 22;
+
+/// Number of paths from `from` to `to` that go through every node of `via`, in any order.
+///
+/// Fails if the graph has a cycle, as there could be infinitely many paths then, if the nodes
+/// and subsets of distinct mandatory nodes need more than [`MAX_COUNTS`] counts, or if the
+/// number of paths does not fit in a `u64`.
+pub fn count_paths<N: Debug, E>(
+    graph: &Graph<N, E, Directed>,
+    from: NodeIndex,
+    to: NodeIndex,
+    via: &[NodeIndex],
+) -> Result<u64> {
+    let order = match toposort(graph, None) {
+        Ok(order) => order,
+        Err(cycle) => bail!("the graph has a cycle through {:?}", graph[cycle.node_id()]),
+    };
+
+    let mut via = via.to_vec();
+    via.sort();
+    via.dedup();
+
+    let subsets = 1_usize.checked_shl(via.len() as u32).filter(|subsets| {
+        subsets
+            .checked_mul(graph.node_count())
+            .is_some_and(|size| size <= MAX_COUNTS)
+    });
+
+    let Some(subsets) = subsets else {
+        bail!(
+            "{} nodes with {} mandatory nodes is too many, at most {MAX_COUNTS} counts fit",
+            graph.node_count(),
+            via.len()
+        );
+    };
+
+    // bit of each node in the subsets of visited mandatory nodes
+    let bit = |n: NodeIndex| via.binary_search(&n).map_or(0, |i| 1 << i);
+    let all = (1 << via.len()) - 1;
+
+    // `None` once a count no longer fits, which only matters if it leads to `to`
+    let mut counts = vec![vec![Some(0_u64); subsets]; graph.node_count()];
+    counts[from.index()][bit(from)] = Some(1);
+
+    for n in order {
+        for visited in 0..counts[n.index()].len() {
+            let count = counts[n.index()][visited];
+
+            if count == Some(0) {
+                continue;
+            }
+
+            for m in graph.neighbors(n) {
+                let next = &mut counts[m.index()][visited | bit(m)];
+                *next = next
+                    .zip(count)
+                    .and_then(|(next, count)| next.checked_add(count));
+            }
+        }
+    }
+
+    counts[to.index()][all]
+        .with_context(|| format!("more paths than fit in a u64 reach {:?}", graph[to]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedDiGraph;
+
+    const DIAMONDS: &str = "a: b c\nb: d\nc: d\nd: e f\ne: g\nf: g\ng:";
+
+    fn count(named: &NamedDiGraph, from: &str, to: &str, via: &[&str]) -> Result<u64> {
+        let via = via
+            .iter()
+            .map(|&name| named.index(name).unwrap())
+            .collect::<Vec<_>>();
+
+        count_paths(
+            &named.graph,
+            named.index(from).unwrap(),
+            named.index(to).unwrap(),
+            &via,
+        )
+    }
+
+    #[test]
+    fn count_paths_test() {
+        let named: NamedDiGraph = DIAMONDS.parse().unwrap();
+
+        assert_eq!(count(&named, "a", "g", &[]).unwrap(), 4);
+        assert_eq!(count(&named, "a", "g", &["b"]).unwrap(), 2);
+        assert_eq!(count(&named, "a", "g", &["f", "b"]).unwrap(), 1);
+        assert_eq!(count(&named, "a", "g", &["b", "c"]).unwrap(), 0);
+        assert_eq!(count(&named, "a", "g", &["b", "b", "a", "g"]).unwrap(), 2);
+        assert_eq!(count(&named, "g", "a", &[]).unwrap(), 0);
+        assert_eq!(count(&named, "d", "d", &[]).unwrap(), 1);
+    }
+
+    #[test]
+    fn cycle_test() {
+        let named: NamedDiGraph = "a: b\nb: c\nc: b".parse().unwrap();
+
+        let err = count(&named, "a", "c", &[]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    /// `diamonds` diamonds one after the other, `2^diamonds` paths from the first node to the last.
+    fn ladder(diamonds: usize) -> Graph<usize, ()> {
+        let mut graph = Graph::new();
+        let mut last = graph.add_node(0);
+
+        for i in 0..diamonds {
+            let (left, right, next) = (
+                graph.add_node(3 * i + 1),
+                graph.add_node(3 * i + 2),
+                graph.add_node(3 * i + 3),
+            );
+
+            graph.extend_with_edges([(last, left), (last, right), (left, next), (right, next)]);
+            last = next;
+        }
+
+        graph
+    }
+
+    #[test]
+    fn too_many_via_test() {
+        let named: NamedDiGraph = DIAMONDS.parse().unwrap();
+
+        // repeats share a bit
+        let via = vec!["b"; 100];
+        assert_eq!(count(&named, "a", "g", &via).unwrap(), 2);
+
+        let graph = ladder(10);
+        let (from, to) = (NodeIndex::new(0), NodeIndex::new(30));
+
+        let via = (0..15).map(NodeIndex::new).collect::<Vec<_>>();
+        assert_eq!(count_paths(&graph, from, to, &via).unwrap(), 0);
+
+        let via = (0..30).map(NodeIndex::new).collect::<Vec<_>>();
+        let err = count_paths(&graph, from, to, &via).unwrap_err();
+        assert!(err.to_string().contains("too many"));
+    }
+
+    #[test]
+    fn overflow_test() {
+        let graph = ladder(64);
+        let (from, to) = (NodeIndex::new(0), NodeIndex::new(3 * 64));
+
+        assert_eq!(
+            count_paths(&graph, from, NodeIndex::new(3 * 63), &[]).unwrap(),
+            1 << 63
+        );
+
+        let err = count_paths(&graph, from, to, &[]).unwrap_err();
+        assert!(err.to_string().contains("u64"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Disjoint sets of the numbers `0..n`, for components that only ever merge.
+
+/// Forest where each set hangs from its root, smaller trees always go under larger ones.
+///
+/// Trees stay at most logarithmically deep that way, so finding a root needs no mutable access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Every element on its own.
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+            count: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Root of the set of `a`, the same for every element in it.
+    pub fn find(&self, mut a: usize) -> usize {
+        while self.parents[a] != a {
+            a = self.parents[a];
+        }
+
+        a
+    }
+
+    /// Merges the sets of `a` and `b`, false if they were already the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+
+        if a == b {
+            return false;
+        }
+
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set of `a`.
+    pub fn size(&self, a: usize) -> usize {
+        self.sizes[self.find(a)]
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Size of every set, largest first.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = (0..self.len())
+            .filter(|&a| self.parents[a] == a)
+            .map(|a| self.sizes[a])
+            .collect::<Vec<_>>();
+
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find_test() {
+        let mut sets = UnionFind::new(8);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(5, 6));
+
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 5));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.count(), 4);
+        assert_eq!(sets.sizes(), [4, 2, 1, 1]);
+    }
+}
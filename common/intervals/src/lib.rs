@@ -0,0 +1,385 @@
+//! Sets of integers stored as sorted, disjoint intervals.
+//!
+//! Intervals are half open, `start..end`, so lengths and adjacency need no `+ 1` fix ups.
+//! Inclusive ranges convert on the way in.
+
+use std::{
+    fmt::Debug,
+    ops::{Add, Range, RangeInclusive, Sub},
+};
+
+/// Integer types an interval can be made of.
+pub trait Integer: Copy + Ord + Debug + Default + Add<Output = Self> + Sub<Output = Self> {
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        })*
+    };
+}
+
+integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The integers from `start` up to, but not including, `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    /// From `first` to `last`, both included.
+    ///
+    /// # Panics
+    ///
+    /// If `last` is the largest value of `T`, as there is nothing past it to end the interval at.
+    pub fn inclusive(first: T, last: T) -> Self {
+        let end = last
+            .checked_add(T::ONE)
+            .unwrap_or_else(|| panic!("can not end an interval after {last:?}"));
+
+        Interval::new(first, end)
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Last value in the interval, `None` if it is empty.
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::ONE)
+    }
+}
+
+impl<T: Integer> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Interval::new(range.start, range.end)
+    }
+}
+
+/// Panics like [`Interval::inclusive`] if the range ends at the largest value of `T`.
+impl<T: Integer> From<RangeInclusive<T>> for Interval<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Interval::inclusive(*range.start(), *range.end())
+    }
+}
+
+/// Disjoint intervals sorted by start, with no two of them touching.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// Sorts and merges overlapping or touching intervals, dropping empty ones.
+    fn normalize(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort_by_key(|interval| interval.start);
+
+        let mut res: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+
+        for interval in intervals {
+            match res.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => res.push(interval),
+            }
+        }
+
+        IntervalSet { intervals: res }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn total_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |acc, interval| acc + interval.len())
+    }
+
+    /// Binary search for the only interval that could hold `value`.
+    pub fn contains(&self, value: T) -> bool {
+        let after = self
+            .intervals
+            .partition_point(|interval| interval.start <= value);
+
+        after > 0 && self.intervals[after - 1].contains(value)
+    }
+
+    pub fn insert(&mut self, interval: impl Into<Interval<T>>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval.into());
+
+        *self = Self::normalize(intervals);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.iter().chain(other.iter()).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+
+            if start < end {
+                res.push(Interval::new(start, end));
+            }
+
+            // whichever ends first can not meet anything else from the other set
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { intervals: res }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let mut j = 0;
+
+        for a in &self.intervals {
+            let mut start = a.start;
+
+            // skip what ends before this interval, it may still cut the next one otherwise
+            while other.intervals.get(j).is_some_and(|b| b.end <= start) {
+                j += 1;
+            }
+
+            let mut k = j;
+
+            while let Some(b) = other.intervals.get(k).filter(|b| b.start < a.end) {
+                if start < b.start {
+                    res.push(Interval::new(start, b.start));
+                }
+
+                start = start.max(b.end);
+                k += 1;
+            }
+
+            if start < a.end {
+                res.push(Interval::new(start, a.end));
+            }
+        }
+
+        IntervalSet { intervals: res }
+    }
+
+    /// The values below `point` and the ones from `point` on.
+    pub fn split_at(&self, point: T) -> (Self, Self) {
+        let mut below = vec![];
+        let mut above = vec![];
+
+        for &interval in &self.intervals {
+            if interval.end <= point {
+                below.push(interval);
+            } else if point <= interval.start {
+                above.push(interval);
+            } else {
+                below.push(Interval::new(interval.start, point));
+                above.push(Interval::new(point, interval.end));
+            }
+        }
+
+        (
+            IntervalSet { intervals: below },
+            IntervalSet { intervals: above },
+        )
+    }
+
+    /// Whether every value of `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Whether `self` and `other` have some value in common.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+}
+
+impl<T: Integer, I: Into<Interval<T>>> FromIterator<I> for IntervalSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        Self::normalize(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Integer> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalize(vec![interval])
+    }
+}
+
+impl<T: Integer> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Interval::from(range).into()
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Interval::from(range).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(a, b)| a..b).collect()
+    }
+
+    /// Every set made of some of a few intervals that overlap, touch, nest or are empty.
+    fn all_sets() -> Vec<IntervalSet<i32>> {
+        const INTERVALS: [(i32, i32); 7] =
+            [(0, 3), (2, 6), (5, 5), (6, 9), (8, 15), (12, 13), (20, 30)];
+
+        (0..1 << INTERVALS.len())
+            .map(|mask| {
+                INTERVALS
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .map(|(_, &(a, b))| a..b)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn values(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        (-5..35).filter(|&v| set.contains(v)).collect()
+    }
+
+    #[test]
+    fn normalize_test() {
+        let s = set(&[(5, 8), (1, 3), (3, 4), (7, 10), (12, 12)]);
+
+        assert_eq!(s.intervals(), &[Interval::new(1, 4), Interval::new(5, 10)]);
+        assert_eq!(s.total_len(), 8);
+        assert!(s.contains(1) && s.contains(9) && !s.contains(4) && !s.contains(10));
+
+        let inclusive = [0..=2, 3..=3, 9..=9]
+            .into_iter()
+            .collect::<IntervalSet<u8>>();
+        assert_eq!(inclusive.total_len(), 5);
+        assert_eq!(inclusive.iter().last().unwrap().last(), Some(9));
+
+        let almost = Interval::from(0..=u8::MAX - 1);
+        assert_eq!(almost, Interval::new(0, u8::MAX));
+        assert_eq!(almost.last(), Some(u8::MAX - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "after 255")]
+    fn inclusive_max_test() {
+        let _ = Interval::from(0..=u8::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "after 9223372036854775807")]
+    fn inclusive_max_single_test() {
+        Interval::inclusive(i64::MAX, i64::MAX);
+    }
+
+    #[test]
+    fn operations_test() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20)]));
+
+        let (below, above) = a.split_at(25);
+        assert_eq!(below, set(&[(0, 10), (20, 25)]));
+        assert_eq!(above, set(&[(25, 30)]));
+
+        assert!(set(&[(2, 4)]).is_subset(&a));
+        assert!(!b.is_subset(&a));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&set(&[(10, 20)])));
+
+        let mut c = IntervalSet::new();
+        c.insert(10..=19);
+        c.insert(0..10);
+        assert_eq!(c, set(&[(0, 20)]));
+    }
+
+    #[test]
+    fn brute_force_test() {
+        let sets = all_sets();
+
+        for a in &sets {
+            for b in &sets {
+                let (x, y) = (values(a), values(b));
+
+                assert_eq!(values(&a.union(b)), &x | &y);
+                assert_eq!(values(&a.intersection(b)), &x & &y);
+                assert_eq!(values(&a.difference(b)), &x - &y);
+
+                // results are normalized as well
+                assert_eq!(a.union(b), a.union(b).iter().copied().collect());
+                assert_eq!(a.difference(b), a.difference(b).iter().copied().collect());
+            }
+
+            for point in [-1, 0, 17, 33, 70] {
+                let (below, above) = a.split_at(point);
+
+                assert!(values(&below).iter().all(|&v| v < point));
+                assert!(values(&above).iter().all(|&v| v >= point));
+                assert_eq!(below.union(&above), *a);
+            }
+
+            assert_eq!(a.total_len() as usize, values(a).len());
+        }
+    }
+}
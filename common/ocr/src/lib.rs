@@ -0,0 +1,223 @@
+//! Reads the block letters some puzzles draw instead of giving a number.
+//!
+//! Letters are split at the blank columns between them and matched against the glyphs seen so
+//! far, in the small 4x6 font or the large 6x10 one depending on the height of the drawing.
+
+use std::fmt::Display;
+
+#[rustfmt::skip]
+const SMALL: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Neither 6 nor 10 rows once blank rows above and below are dropped.
+    Height(usize),
+    /// A glyph starting at `column` that is not in the font, drawn with `#` and `.`.
+    UnknownGlyph { column: usize, glyph: String },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::Height(height) => {
+                write!(f, "letters are 6 or 10 pixels tall, not {height}")
+            }
+            OcrError::UnknownGlyph { column, glyph } => {
+                write!(f, "unknown glyph at column {column}:\n{glyph}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+fn column_lit(pixels: &[Vec<bool>], column: usize) -> bool {
+    pixels.iter().any(|row| row.get(column) == Some(&true))
+}
+
+/// Some columns of the pixels drawn with `#` and `.`, the way the fonts are written.
+fn render(pixels: &[Vec<bool>], columns: std::ops::Range<usize>) -> String {
+    pixels
+        .iter()
+        .map(|row| {
+            columns
+                .clone()
+                .map(|c| if row.get(c) == Some(&true) { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn glyphs<const N: usize>(font: &[(char, [&str; N])]) -> Vec<(char, String)> {
+    font.iter()
+        .map(|(letter, rows)| (*letter, rows.join("\n")))
+        .collect()
+}
+
+/// The letters in rows of pixels, `true` where lit.
+pub fn read_pixels(pixels: &[Vec<bool>]) -> Result<String, OcrError> {
+    let lit = |row: &Vec<bool>| row.contains(&true);
+
+    let (Some(top), Some(bottom)) = (pixels.iter().position(lit), pixels.iter().rposition(lit))
+    else {
+        return Ok(String::new());
+    };
+
+    let pixels = &pixels[top..=bottom];
+
+    let font = match pixels.len() {
+        6 => glyphs(SMALL),
+        10 => glyphs(LARGE),
+        height => return Err(OcrError::Height(height)),
+    };
+
+    let width = pixels.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut res = String::new();
+    let mut column = 0;
+
+    while column < width {
+        if !column_lit(pixels, column) {
+            column += 1;
+            continue;
+        }
+
+        let start = column;
+
+        while column < width && column_lit(pixels, column) {
+            column += 1;
+        }
+
+        let glyph = render(pixels, start..column);
+
+        let Some((letter, _)) = font.iter().find(|(_, g)| *g == glyph) else {
+            return Err(OcrError::UnknownGlyph {
+                column: start,
+                glyph,
+            });
+        };
+
+        res.push(*letter);
+    }
+
+    Ok(res)
+}
+
+/// The letters in a drawing where `#` or `█` are lit pixels and anything else is dark.
+pub fn read(drawing: &str) -> Result<String, OcrError> {
+    let pixels = drawing
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .collect::<Vec<_>>();
+
+    read_pixels(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `text` the way puzzles do, with a blank column after each letter.
+    fn draw<const N: usize>(font: &[(char, [&str; N])], text: &str) -> String {
+        (0..N)
+            .map(|row| {
+                text.chars()
+                    .map(|c| {
+                        let (_, glyph) = font.iter().find(|(l, _)| *l == c).unwrap();
+                        format!("{}.", glyph[row])
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn small_test() {
+        let letters = SMALL.iter().map(|(l, _)| *l).collect::<String>();
+
+        assert_eq!(read(&draw(SMALL, &letters)).unwrap(), letters);
+
+        // as the CRT of 2022 day 10 shows it
+        let crt = "\
+###..#....###...##..####.###...##..#....
+#..#.#....#..#.#..#....#.#..#.#..#.#....
+#..#.#....#..#.#..#...#..###..#....#....
+###..#....###..####..#...#..#.#....#....
+#....#....#....#..#.#....#..#.#..#.#....
+#....####.#....#..#.####.###...##..####.
+";
+
+        assert_eq!(read(crt).unwrap(), "PLPAZBCL");
+        assert_eq!(
+            read(&format!("\n{}\n\n", crt.replace('#', "█"))).unwrap(),
+            "PLPAZBCL"
+        );
+    }
+
+    #[test]
+    fn large_test() {
+        let letters = LARGE.iter().map(|(l, _)| *l).collect::<String>();
+
+        assert_eq!(read(&draw(LARGE, &letters)).unwrap(), letters);
+    }
+
+    #[test]
+    fn errors_test() {
+        assert_eq!(read("").unwrap(), "");
+        assert_eq!(read("#\n#\n#"), Err(OcrError::Height(3)));
+
+        let drawing = draw(SMALL, "HI")
+            .lines()
+            .map(|line| format!("{line}##"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(
+            read(&drawing),
+            Err(OcrError::UnknownGlyph {
+                column: 9,
+                glyph: ["##"; 6].join("\n")
+            })
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Images and animations of grid simulations, drawn from the text each day already prints.
+//!
+//! Every character of a rendering is a tile, the [`Palette`] gives each tile kind a color.
+//! With the `terminal` feature the same renderings can be played back in the terminal.
+
+#[cfg(feature = "terminal")]
+pub mod terminal;
+
+use anyhow::{bail, Result};
+use std::{collections::HashMap, io::Write};
+
+pub type Rgb = [u8; 3];
+
+pub const WHITE: Rgb = [0xff, 0xff, 0xff];
+pub const BLACK: Rgb = [0x00, 0x00, 0x00];
+pub const GRAY: Rgb = [0x70, 0x70, 0x78];
+pub const SAND: Rgb = [0xe0, 0xb0, 0x50];
+pub const BROWN: Rgb = [0x8b, 0x5a, 0x2b];
+pub const RED: Rgb = [0xd0, 0x30, 0x30];
+pub const GREEN: Rgb = [0x30, 0xa0, 0x40];
+pub const BLUE: Rgb = [0x30, 0x60, 0xd0];
+
+/// Colors by tile, anything else is drawn with the background.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colors: Vec<Rgb>,
+    tiles: HashMap<char, u8>,
+}
+
+impl Palette {
+    pub fn new(background: Rgb) -> Self {
+        Palette {
+            colors: vec![background],
+            tiles: HashMap::new(),
+        }
+    }
+
+    /// Paints `tile` with `color`, a palette holds at most 256 colors, background included.
+    pub fn tile(mut self, tile: char, color: Rgb) -> Self {
+        let index = match self.colors.iter().position(|&c| c == color) {
+            Some(index) => index,
+            None => {
+                assert!(self.colors.len() < 256, "too many colors for a palette");
+                self.colors.push(color);
+                self.colors.len() - 1
+            }
+        };
+
+        self.tiles.insert(tile, index as u8);
+        self
+    }
+
+    fn index(&self, tile: char) -> u8 {
+        self.tiles.get(&tile).copied().unwrap_or(0)
+    }
+
+    fn rgb(&self) -> Vec<u8> {
+        self.colors.iter().flatten().copied().collect()
+    }
+}
+
+/// A rendering as palette indices, one per tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Blank lines around the text, as `Debug` renderings often start with one, are dropped and
+    /// short lines padded with the background.
+    pub fn from_text(text: &str, palette: &Palette) -> Self {
+        let lines = text
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        let height = lines
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |last| last + 1);
+
+        let width = lines[..height]
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut pixels = vec![0; width * height];
+
+        for (y, line) in lines[..height].iter().enumerate() {
+            for (x, tile) in line.chars().enumerate() {
+                pixels[y * width + x] = palette.index(tile);
+            }
+        }
+
+        Frame {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// `scale` by `scale` pixels per tile on a `width` by `height` tile canvas, top left aligned.
+    fn pixels(&self, width: usize, height: usize, scale: usize) -> Vec<u8> {
+        let mut res = vec![0; width * height * scale * scale];
+
+        for y in 0..self.height * scale {
+            for x in 0..self.width * scale {
+                res[y * width * scale + x] = self.pixels[(y / scale) * self.width + x / scale];
+            }
+        }
+
+        res
+    }
+
+    pub fn write_png(&self, w: impl Write, palette: &Palette, scale: usize) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            bail!("nothing to draw");
+        }
+
+        let mut encoder =
+            png::Encoder::new(w, (self.width * scale) as u32, (self.height * scale) as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(palette.rgb());
+
+        encoder
+            .write_header()?
+            .write_image_data(&self.pixels(self.width, self.height, scale))?;
+
+        Ok(())
+    }
+}
+
+/// Renders a single frame to a PNG file.
+pub fn save_png(path: &str, text: &str, palette: &Palette, scale: usize) -> Result<()> {
+    Frame::from_text(text, palette).write_png(std::fs::File::create(path)?, palette, scale)
+}
+
+/// Frames of a run, keeping one every few so long simulations stay watchable.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    palette: Palette,
+    scale: usize,
+    delay: u16,
+    every: usize,
+    frames: Vec<Frame>,
+    skipped: Option<Frame>,
+    seen: usize,
+}
+
+impl Animation {
+    pub fn new(palette: Palette, scale: usize) -> Self {
+        Animation {
+            palette,
+            scale,
+            delay: 10,
+            every: 1,
+            frames: vec![],
+            skipped: None,
+            seen: 0,
+        }
+    }
+
+    /// Hundredths of a second each frame is shown.
+    pub fn delay(mut self, delay: u16) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Keeps one frame in `every`, the last one is always kept.
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    pub fn push(&mut self, text: &str) {
+        let frame = Frame::from_text(text, &self.palette);
+
+        if self.seen.is_multiple_of(self.every) {
+            self.frames.push(frame);
+            self.skipped = None;
+        } else {
+            self.skipped = Some(frame);
+        }
+
+        self.seen += 1;
+    }
+
+    /// Frames the animation will show.
+    pub fn len(&self) -> usize {
+        self.frames.len() + self.skipped.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Looping GIF on a canvas as large as the largest frame.
+    pub fn write_gif(&self, w: impl Write) -> Result<()> {
+        let frames = self.frames.iter().chain(&self.skipped).collect::<Vec<_>>();
+
+        let width = frames.iter().map(|f| f.width).max().unwrap_or(0);
+        let height = frames.iter().map(|f| f.height).max().unwrap_or(0);
+
+        if width == 0 || height == 0 {
+            bail!("nothing to draw");
+        }
+
+        let (Ok(w_px), Ok(h_px)) = (
+            u16::try_from(width * self.scale),
+            u16::try_from(height * self.scale),
+        ) else {
+            bail!(
+                "{width}x{height} tiles at scale {} do not fit a GIF",
+                self.scale
+            );
+        };
+
+        let mut encoder = gif::Encoder::new(w, w_px, h_px, &self.palette.rgb())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for frame in frames {
+            let mut gif_frame = gif::Frame::from_indexed_pixels(
+                w_px,
+                h_px,
+                frame.pixels(width, height, self.scale),
+                None,
+            );
+            gif_frame.delay = self.delay;
+
+            encoder.write_frame(&gif_frame)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn save_gif(&self, path: &str) -> Result<()> {
+        self.write_gif(std::fs::File::create(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Palette {
+        Palette::new(WHITE)
+            .tile('#', GRAY)
+            .tile('O', BROWN)
+            .tile('@', RED)
+    }
+
+    #[test]
+    fn palette_test() {
+        let palette = palette().tile('[', BROWN).tile(']', BROWN);
+
+        assert_eq!(palette.colors.len(), 4);
+        assert_eq!(palette.index('['), palette.index('O'));
+        assert_eq!(palette.index('.'), 0);
+    }
+
+    #[test]
+    fn frame_test() {
+        let frame = Frame::from_text("\n#O\n#@..\n\n", &palette());
+
+        assert_eq!((frame.width, frame.height), (4, 2));
+        assert_eq!(frame.pixels, vec![1, 2, 0, 0, 1, 3, 0, 0]);
+        assert_eq!(frame.pixels(4, 2, 2)[..8], [1, 1, 2, 2, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn png_test() {
+        let mut png = vec![];
+
+        Frame::from_text("#O\n.@", &palette())
+            .write_png(&mut png, &palette(), 3)
+            .unwrap();
+
+        assert_eq!(&png[1..4], b"PNG");
+        assert!(Frame::from_text("\n\n", &palette())
+            .write_png(vec![], &palette(), 1)
+            .is_err());
+    }
+
+    #[test]
+    fn gif_test() {
+        let mut animation = Animation::new(palette(), 2).every(3);
+
+        for i in 0..8 {
+            animation.push(&format!("#{}@", ".".repeat(i)));
+        }
+
+        // frames 0, 3, 6 and the last one
+        assert_eq!(animation.len(), 4);
+
+        let mut gif = vec![];
+        animation.write_gif(&mut gif).unwrap();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        // canvas fits the widest frame, 9 tiles at scale 2
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 18);
+    }
+}
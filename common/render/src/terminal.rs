@@ -0,0 +1,275 @@
+//! Plays the text renderings of a simulation in the terminal, one frame at a time.
+//!
+//! Space pauses, `+` and `-` change the speed, `n` or the right arrow steps while paused
+//! and `q` quits. The view follows the tiles that change between frames.
+
+use anyhow::Result;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+type Tiles = Vec<Vec<char>>;
+
+fn tiles(text: &str) -> Tiles {
+    text.lines()
+        .skip_while(|line| line.trim().is_empty())
+        .map(|line| line.chars().collect())
+        .collect()
+}
+
+/// Center of the tiles that differ between two frames, `None` if they are the same.
+pub fn changes(prev: &[Vec<char>], next: &[Vec<char>]) -> Option<(usize, usize)> {
+    let mut min = (usize::MAX, usize::MAX);
+    let mut max = (0, 0);
+
+    for y in 0..prev.len().max(next.len()) {
+        let a = prev.get(y).map_or(&[][..], |row| &row[..]);
+        let b = next.get(y).map_or(&[][..], |row| &row[..]);
+
+        for x in 0..a.len().max(b.len()) {
+            if a.get(x) != b.get(x) {
+                min = (min.0.min(x), min.1.min(y));
+                max = (max.0.max(x), max.1.max(y));
+            }
+        }
+    }
+
+    (min.0 != usize::MAX).then_some(((min.0 + max.0) / 2, (min.1 + max.1) / 2))
+}
+
+/// The part of a frame shown in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Viewport {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    /// Recenters on `focus` once it gets within a quarter of the view from an edge, without
+    /// scrolling past the `size` of the frame.
+    pub fn follow(&mut self, focus: (usize, usize), size: (usize, usize)) {
+        fn axis(start: &mut usize, len: usize, focus: usize, size: usize) {
+            let margin = len / 4;
+
+            if focus < *start + margin || *start + len - margin <= focus {
+                *start = focus.saturating_sub(len / 2);
+            }
+
+            *start = (*start).min(size.saturating_sub(len));
+        }
+
+        axis(&mut self.x, self.width, focus.0, size.0);
+        axis(&mut self.y, self.height, focus.1, size.1);
+    }
+}
+
+/// Restores the terminal even if playing fails midway.
+struct Screen;
+
+impl Screen {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub struct Player {
+    fps: f64,
+    paused: bool,
+}
+
+impl Player {
+    pub fn new(fps: f64) -> Self {
+        Player { fps, paused: false }
+    }
+
+    /// Starts paused on the first frame.
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    fn draw(&self, frame: &Tiles, view: &Viewport, index: usize, done: bool) -> Result<()> {
+        let mut out = std::io::stdout();
+
+        for row in 0..view.height {
+            let line = frame
+                .get(view.y + row)
+                .map(|tiles| {
+                    tiles
+                        .iter()
+                        .skip(view.x)
+                        .take(view.width)
+                        .collect::<String>()
+                })
+                .unwrap_or_default();
+
+            queue!(
+                out,
+                MoveTo(0, row as u16),
+                Print(line),
+                Clear(ClearType::UntilNewLine)
+            )?;
+        }
+
+        let state = match (done, self.paused) {
+            (true, _) => "done",
+            (false, true) => "paused",
+            (false, false) => "playing",
+        };
+
+        let status = format!(
+            "frame {index} | {:.1} fps | {state} | space pause, +/- speed, n step, q quit",
+            self.fps
+        );
+
+        queue!(
+            out,
+            MoveTo(0, view.height as u16),
+            Print(status.chars().take(view.width).collect::<String>()),
+            Clear(ClearType::UntilNewLine)
+        )?;
+
+        out.flush()?;
+
+        Ok(())
+    }
+
+    /// Plays the `frames` until they run out and the user quits.
+    pub fn play(&mut self, frames: impl IntoIterator<Item = String>) -> Result<()> {
+        let mut frames = frames.into_iter();
+
+        let Some(first) = frames.next() else {
+            return Ok(());
+        };
+
+        let _screen = Screen::enter()?;
+
+        let mut current = tiles(&first);
+        let mut index = 0;
+        let mut done = false;
+        let mut view = Viewport::default();
+        let mut last = Instant::now();
+
+        loop {
+            let (columns, rows) = terminal::size()?;
+            view.width = columns as usize;
+            view.height = (rows as usize).saturating_sub(1);
+
+            self.draw(&current, &view, index, done)?;
+
+            let mut step = false;
+
+            let wait = match self.paused || done {
+                true => Duration::from_secs(3600),
+                false => Duration::from_secs_f64(1.0 / self.fps).saturating_sub(last.elapsed()),
+            };
+
+            if event::poll(wait)? {
+                let Event::Key(key) = event::read()? else {
+                    continue;
+                };
+
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Char(' ') => self.paused = !self.paused,
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        self.fps = (self.fps * 1.5).min(1000.0)
+                    }
+                    KeyCode::Char('-') => self.fps = (self.fps / 1.5).max(0.1),
+                    KeyCode::Char('n') | KeyCode::Right => step = true,
+                    _ => {}
+                }
+            } else {
+                step = true;
+            }
+
+            if !step || done {
+                continue;
+            }
+
+            last = Instant::now();
+
+            match frames.next() {
+                Some(next) => {
+                    let next = tiles(&next);
+
+                    if let Some(focus) = changes(&current, &next) {
+                        let size = (next.iter().map(Vec::len).max().unwrap_or(0), next.len());
+                        view.follow(focus, size);
+                    }
+
+                    current = next;
+                    index += 1;
+                }
+                None => done = true,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_test() {
+        let a = tiles("\n....\n.#..\n....");
+        let b = tiles("\n....\n..#.\n....");
+
+        assert_eq!(changes(&a, &a), None);
+        assert_eq!(changes(&a, &b), Some((1, 1)));
+        // a row that was not there before changed as a whole
+        assert_eq!(changes(&a, &tiles("....\n.#..\n....\n...#")), Some((1, 3)));
+    }
+
+    #[test]
+    fn follow_test() {
+        let mut view = Viewport {
+            x: 0,
+            y: 0,
+            width: 20,
+            height: 10,
+        };
+
+        // well inside the view, nothing moves
+        view.follow((10, 5), (100, 100));
+        assert_eq!((view.x, view.y), (0, 0));
+
+        view.follow((18, 5), (100, 100));
+        assert_eq!((view.x, view.y), (8, 0));
+
+        // never past the end of the frame
+        view.follow((99, 99), (100, 100));
+        assert_eq!((view.x, view.y), (80, 90));
+
+        // frames smaller than the view stay put
+        view.follow((3, 3), (5, 5));
+        assert_eq!((view.x, view.y), (0, 0));
+    }
+}